@@ -1,23 +1,110 @@
 use engine;
-use engine::wgpu;
+use engine::audio::Audio;
+use engine::camera::CameraController;
+use engine::config::{self, Config, Problems};
+use engine::debug::DebugOverlay;
+use engine::devtext::DevText;
+use engine::difficulty::{Curve, Difficulty, Measure};
+use engine::ease::Ease;
+use engine::ecs::{Collider, Entity, Visual, World};
+use engine::events::EventBus;
+use engine::hud::{Anchor, Hud, HudContent, HudElement, HudId};
+use engine::particles::{Emitter, Particles};
+use engine::rng::GameRng;
+use engine::save::{player_name, HighScores, ScoreEntry};
+use engine::snapshot::{self, Snapshot};
+use engine::spawner::{Placement, SpawnRule, Spawner};
+use engine::text::TextStyle;
+use engine::transition::{self, Overlay, Transition, TransitionKind};
+use engine::ui::{Ui, UiSkin};
 use engine::{geom::*, sprites::*, Camera, Engine, SheetRegion, Transform, Zeroable};
-use kira::{
-    manager::{backend::DefaultBackend, AudioManager, AudioManagerSettings},
-    sound::static_sound::{StaticSoundData, StaticSoundSettings},
-};
-use rand::{distributions::Uniform, Rng};
+use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::ops::Range;
 const W: f32 = 768.0;
 const H: f32 = 1280.0;
-const GUY_SPEED: f32 = 4.0;
-const PAVEMENT_SPEED: f32 = -1.0;
 const SPRITE_MAX: usize = 1000;
-const COLLISION_DISTANCE: f32 = 22.0;
-const DROP_OFF_DIST: f32 = 75.0;
 const COLLISION_STEPS: usize = 3;
 const GUY_Y_POS: f32 = 24.0;
+// the tuning file in the content folder
+const TUNING_FILE: &str = "drive-dilemma.toml";
+
+// values for balancing the game, read from `TUNING_FILE`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Tuning {
+    // how far the bus moves left or right each tick
+    bus_speed: f32,
+    // and up or down
+    bus_vertical_speed: f32,
+    // how far the pavements scroll each tick
+    pavement_speed: f32,
+    // the bus hits an animal or picks someone up when their centers are this close
+    collision_distance: f32,
+    // and drops a passenger off at a building this close
+    drop_off_distance: f32,
+    // x range animals and people appear in
+    spawn_x: Range<f32>,
+    // x positions of the two rows of buildings
+    building_lanes: Vec<f32>,
+    // ticks between animals, people and buildings, chosen uniformly
+    animal_interval: Range<u32>,
+    person_interval: Range<u32>,
+    building_interval: Range<u32>,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Tuning {
+            bus_speed: 4.0,
+            bus_vertical_speed: 8.0,
+            pavement_speed: -1.0,
+            collision_distance: 22.0,
+            drop_off_distance: 75.0,
+            spawn_x: 150.0..W - 150.0,
+            building_lanes: vec![100.0, W - 100.0],
+            animal_interval: 30..90,
+            person_interval: 30..180,
+            building_interval: 30..90,
+        }
+    }
+}
+
+impl Config for Tuning {
+    fn validate(&self, problems: &mut Problems) {
+        problems.positive("bus_speed", self.bus_speed);
+        problems.positive("bus_vertical_speed", self.bus_vertical_speed);
+        problems.positive("collision_distance", self.collision_distance);
+        problems.at_least("drop_off_distance", self.drop_off_distance, self.collision_distance);
+        problems.check(self.spawn_x.start <= self.spawn_x.end, || {
+            format!("spawn_x must not run backwards, not {:?}", self.spawn_x)
+        });
+        problems.not_empty("building_lanes", &self.building_lanes);
+        problems.ticks("animal_interval", &self.animal_interval);
+        problems.ticks("person_interval", &self.person_interval);
+        problems.ticks("building_interval", &self.building_interval);
+    }
+}
+
+impl Tuning {
+    fn placement(&self, thing: &Thing) -> Placement {
+        match thing {
+            Thing::Building(_) => Placement::Lanes(self.building_lanes.clone()),
+            _ => Placement::Range(self.spawn_x.start, self.spawn_x.end),
+        }
+    }
+
+    fn interval(&self, thing: &Thing) -> Range<u32> {
+        match thing {
+            Thing::Animal(_) => self.animal_interval.clone(),
+            Thing::Person(_) => self.person_interval.clone(),
+            Thing::Building(_) => self.building_interval.clone(),
+        }
+    }
+}
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 enum Job {
     Doctor,
     Firefighter,
@@ -25,6 +112,7 @@ enum Job {
     Cop,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 enum CatDog {
     Cat,
     Dog,
@@ -41,22 +129,73 @@ impl fmt::Display for Job {
     }
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 struct Person {
     pos: Vec2,
     vel: Vec2,
     job: Job,
 }
 
-struct Building {
-    pos: Vec2,
-    vel: Vec2,
-    job: Job,
+// what happens during a run, for sound, effects and scoring to react to
+#[derive(Clone, Debug)]
+enum GameEvent {
+    PassengerBoarded { job: Job },
+    PassengerDropped { job: Job, pos: Vec2 },
+    Crashed { pos: Vec2 },
 }
 
-struct Animal {
-    pos: Vec2,
-    vel: Vec2,
-    animal_type: CatDog,
+// the kinds of entity that scroll down the road
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum Thing {
+    Animal(CatDog),
+    Person(Job),
+    Building(Job),
+}
+
+impl Thing {
+    fn spawn(self, world: &mut World<Thing>, pos: Vec2, radius: f32) -> Entity {
+        let (size, uv) = match &self {
+            Thing::Animal(CatDog::Cat) => (
+                Vec2 { x: 38.4, y: 65.33 },
+                SheetRegion::new(0, 113, 564, 3, 27, 29),
+            ),
+            Thing::Animal(CatDog::Dog) => (
+                Vec2 { x: 38.4, y: 65.33 },
+                SheetRegion::new(0, 146, 565, 3, 25, 27),
+            ),
+            // people are animated every frame in `render`
+            Thing::Person(_) => (Vec2 { x: 38.4, y: 65.33 }, SheetRegion::zeroed()),
+            Thing::Building(job) => (
+                Vec2 { x: 60.0, y: 80.0 },
+                match job {
+                    Job::Firefighter => SheetRegion::new(0, 132, 518, 1, 44, 42),
+                    Job::Doctor => SheetRegion::new(0, 212, 528, 1, 30, 30),
+                    Job::Cop => SheetRegion::new(0, 176, 530, 1, 30, 28),
+                    Job::Regular => SheetRegion::new(0, 97, 528, 1, 32, 33),
+                },
+            ),
+        };
+        let entity = world.spawn(self, pos);
+        world.velocities.insert(entity, Vec2 { x: 0.0, y: -2.0 });
+        world.visuals.insert(entity, Visual { size, uv });
+        world.colliders.insert(
+            entity,
+            Collider { radius },
+        );
+        entity
+    }
+
+    fn is_animal(&self) -> bool {
+        matches!(self, Thing::Animal(_))
+    }
+
+    fn is_person(&self) -> bool {
+        matches!(self, Thing::Person(_))
+    }
+
+    fn is_building(&self) -> bool {
+        matches!(self, Thing::Building(_))
+    }
 }
 
 enum GameState {
@@ -66,73 +205,264 @@ enum GameState {
 }
 
 struct Game {
-    camera: engine::Camera,
+    tuning: Tuning,
+    // follows the bus and shakes on crashes
+    camera: CameraController,
     walls: Vec<SPRITE>,
     bus: Sprite,
-    animals: Vec<Animal>,
-    people: Vec<Person>,
-    animal_timer: u32,
-    people_timer: u32,
-    animal_speed_multiplier: f32,
-    building_speed_multiplier: f32,
-    buildings: Vec<Building>,
-    building_timer: u32,
+    things: World<Thing>,
+    spawner: Spawner<Thing>,
+    // index of the animal rule in `spawner`
+    animal_rule: usize,
+    difficulty: Difficulty,
+    // scroll speed multiplier for animals and buildings
+    speed_curve: Curve,
+    // animal spawn rate multiplier
+    animal_rate_curve: Curve,
+    // how likely a new animal is to be a dog rather than a cat
+    dog_weight_curve: Curve,
     pavements: Vec<Sprite>,
     score: u32,
-    font: engine::BitFont,
-    font_end: engine::BitFont,
+    hud: Hud,
+    score_text: HudId,
+    level_text: HudId,
+    // HUD sprites showing who is on the bus
+    bus_seats: Vec<HudId>,
+    font_end: engine::text::Font,
     curr_frame: usize,
     frame_counter: usize,
     frame_direction: isize,
     game_over: bool,
     game_state: GameState,
+    start_group: usize,
+    end_group: usize,
+    // menus and the game over summary
+    ui: Ui,
+    ui_group: usize,
+    // the screen transition in progress, and the state to switch to once it
+    // covers the screen
+    transition: Option<Transition>,
+    next_state: Option<GameState>,
+    // solid black, for fading out of gameplay
+    fade: Overlay,
+    particles: Particles,
+    // colliders, hitboxes and stats, shown with the engine's debug key
+    debug: DebugOverlay,
+    // the console and the log view, drawn over everything in a group of their own
+    dev_text: DevText,
+    // emitter indices in `particles`
+    drop_off_burst: usize,
+    crash_dust: usize,
+    events: EventBus<GameEvent, Game>,
+    rng: GameRng,
+    high_scores: HighScores,
     on_bus: Vec<Person>,
     // sound
-    audio_manager: AudioManager<DefaultBackend>,
+    audio: Audio,
     drop_sound: StaticSoundData,
     // cat_sound: StaticSoundData,
 }
 
+impl Game {
+    // the camera the current scene is drawn with
+    fn active_camera(&self) -> engine::Camera {
+        match self.game_state {
+            GameState::InGame => self.camera.camera(),
+            _ => self.camera.screen_camera(),
+        }
+    }
+
+    fn keep_score(&mut self, event: &GameEvent) {
+        if let GameEvent::PassengerDropped { .. } = event {
+            self.score += 1;
+        }
+    }
+
+    fn play_sounds(&mut self, event: &GameEvent) {
+        if let GameEvent::PassengerDropped { .. } = event {
+            self.audio.play(&self.drop_sound);
+        }
+    }
+
+    fn show_effects(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::PassengerDropped { pos, .. } => {
+                self.particles.burst(self.drop_off_burst, pos, 12)
+            }
+            GameEvent::Crashed { pos } => {
+                self.camera.add_trauma(0.8);
+                self.particles.burst(self.crash_dust, pos, 16);
+            }
+            GameEvent::PassengerBoarded { .. } => (),
+        }
+    }
+
+    // fade to black, then in on the end screen
+    fn end_run(&mut self, event: &GameEvent) {
+        if let GameEvent::Crashed { .. } = event {
+            if self.transition.is_none() {
+                self.transition = Some(Transition::fade(self.fade, 60));
+                self.next_state = Some(GameState::GameOver);
+                self.record_score();
+            }
+        }
+    }
+
+    fn print_stats(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::PassengerBoarded { job } => {
+                log::debug!(target: "bus", "picked up a {job}, {} on the bus", self.on_bus.len());
+            }
+            GameEvent::PassengerDropped { job, .. } => {
+                log::debug!(target: "bus", "dropped off a {job}, {} on the bus", self.on_bus.len());
+            }
+            GameEvent::Crashed { .. } => log::info!(target: "score", "final score: {}", self.score),
+        }
+    }
+
+    // loads the tuning file again if it changed on disk, keeping the current
+    // values if the new ones can't be used
+    fn reload_tuning(&mut self, engine: &mut Engine) {
+        let changed = engine.assets.take_changed();
+        if !changed.iter().any(|path| path.as_os_str() == TUNING_FILE) {
+            return;
+        }
+        match config::load::<Tuning>(&engine.assets, TUNING_FILE) {
+            Ok(tuning) => {
+                log::info!(target: "config", "reloaded {TUNING_FILE}");
+                for rule in self.spawner.rules.iter_mut() {
+                    let Some((thing, _)) = rule.kinds.first() else {
+                        continue;
+                    };
+                    rule.placement = tuning.placement(thing);
+                    rule.interval = tuning.interval(thing);
+                    rule.min_spacing = tuning.collision_distance;
+                }
+                engine.console.set("bus_speed", tuning.bus_speed);
+                self.tuning = tuning;
+            }
+            Err(err) => log::error!(target: "config", "{TUNING_FILE}: {err}"),
+        }
+    }
+
+    // runs the commands typed into the developer console
+    fn run_commands(&mut self, engine: &mut Engine) {
+        for command in engine.console.take_commands() {
+            let arg = command.args.first().map(String::as_str);
+            match (command.name.as_str(), arg) {
+                ("score", Some(score)) => match score.parse() {
+                    Ok(score) => self.score = score,
+                    Err(_) => engine.console.print("score takes a whole number"),
+                },
+                ("score", None) => engine.console.print("score <n>"),
+                (name, _) => engine.console.print(&format!("{name} is not handled")),
+            }
+        }
+    }
+
+    // add the final score to the high score table and save it
+    fn record_score(&mut self) {
+        let entry = ScoreEntry::new(&player_name(), self.score, self.rng.seed());
+        if self.high_scores.submit(entry).is_some() {
+            if let Err(err) = self.high_scores.save() {
+                log::error!(target: "save", "could not save high scores: {err}");
+            }
+        }
+    }
+}
+
+// everything that changes during a run, for quick save (F5) and load (F9)
+#[derive(Serialize, Deserialize)]
+struct SavedRun {
+    bus: Sprite,
+    things: World<Thing>,
+    spawner: Spawner<Thing>,
+    difficulty: Difficulty,
+    pavements: Vec<Sprite>,
+    score: u32,
+    curr_frame: usize,
+    frame_counter: usize,
+    frame_direction: isize,
+    on_bus: Vec<Person>,
+    rng: GameRng,
+}
+
+impl Snapshot for Game {
+    type State = SavedRun;
+    const NAME: &'static str = "drive-dilemma";
+
+    fn snapshot(&self) -> SavedRun {
+        SavedRun {
+            bus: self.bus.clone(),
+            things: self.things.clone(),
+            spawner: self.spawner.clone(),
+            difficulty: self.difficulty.clone(),
+            pavements: self.pavements.clone(),
+            score: self.score,
+            curr_frame: self.curr_frame,
+            frame_counter: self.frame_counter,
+            frame_direction: self.frame_direction,
+            on_bus: self.on_bus.clone(),
+            rng: self.rng.clone(),
+        }
+    }
+
+    fn restore(&mut self, _engine: &mut Engine, state: SavedRun) {
+        self.bus = state.bus;
+        self.things = state.things;
+        self.spawner = state.spawner;
+        self.difficulty = state.difficulty;
+        self.pavements = state.pavements;
+        self.score = state.score;
+        self.curr_frame = state.curr_frame;
+        self.frame_counter = state.frame_counter;
+        self.frame_direction = state.frame_direction;
+        self.on_bus = state.on_bus;
+        self.rng = state.rng;
+        self.particles.clear();
+        self.events.clear();
+    }
+}
+
 impl engine::Game for Game {
     // create new game instance
     fn new(engine: &mut Engine) -> Self {
+        let tuning: Tuning = config::load_or_default(&engine.assets, TUNING_FILE);
+
+        // knobs for tuning from the developer console
+        let console = &mut engine.console;
+        console.cvar("bus_speed", tuning.bus_speed, "how far the bus moves left or right each tick");
+        console.cvar("spawn_rate", 1.0f32, "multiplies how often animals, people and buildings appear");
+        console.cvar("god_mode", false, "animals walk through the bus");
+        console.command("score", "score <n> sets the score");
+
         let camera = Camera {
             screen_pos: [0.0, 0.0],
             screen_size: [W, H],
         };
         #[cfg(not(target_arch = "wasm32"))]
-        let start_img = image::open("../content/title_screen_game2.png")
-            .unwrap()
-            .into_rgba8();
-        let start_tex = engine.renderer.gpu.create_texture(
-            &start_img,
-            wgpu::TextureFormat::Rgba8UnormSrgb,
-            start_img.dimensions(),
-            Some("start-sprite.png"),
-        );
-        let end_img = image::open("../content/end_screen_game2.png")
-            .unwrap()
-            .into_rgba8();
-        let end_tex = engine.renderer.gpu.create_texture(
-            &end_img,
-            wgpu::TextureFormat::Rgba8UnormSrgb,
-            end_img.dimensions(),
-            Some("end-sprite.png"),
-        );
-
-        let sprite_img = image::open("../content/run-spritesheet.png")
-            .unwrap()
-            .into_rgba8();
-        let sprite_tex = engine.renderer.gpu.create_texture(
-            &sprite_img,
-            wgpu::TextureFormat::Rgba8UnormSrgb,
-            sprite_img.dimensions(),
-            Some("spr-demo.png"),
-        );
+        let start_tex = engine
+            .assets
+            .load_texture(&engine.renderer.gpu, "title_screen_game2.png")
+            .unwrap();
+        let end_tex = engine
+            .assets
+            .load_texture(&engine.renderer.gpu, "end_screen_game2.png")
+            .unwrap();
+
+        let ui_tex = engine
+            .assets
+            .load_texture(&engine.renderer.gpu, "ascii.png")
+            .unwrap();
+
+        let sprite_tex = engine
+            .assets
+            .load_texture(&engine.renderer.gpu, "run-spritesheet.png")
+            .unwrap();
 
         engine.renderer.sprites.add_sprite_group(
             &engine.renderer.gpu,
-            &sprite_tex,
+            engine.assets.texture(sprite_tex),
             // &title_screen_tex,
             vec![Transform::zeroed(); SPRITE_MAX], //bg, three walls, bus, a few animals
             vec![SheetRegion::zeroed(); SPRITE_MAX],
@@ -140,23 +470,37 @@ impl engine::Game for Game {
         );
 
         // start sprite group
-        engine.renderer.sprites.add_sprite_group(
+        let start_group = engine.renderer.sprites.add_sprite_group(
             &engine.renderer.gpu,
-            &start_tex,
+            engine.assets.texture(start_tex),
             vec![Transform::zeroed(); 1],
             vec![SheetRegion::zeroed(); 1],
             camera,
         );
 
         // end sprite group
-        engine.renderer.sprites.add_sprite_group(
+        let end_group = engine.renderer.sprites.add_sprite_group(
             &engine.renderer.gpu,
-            &end_tex,
+            engine.assets.texture(end_tex),
             vec![Transform::zeroed(); 1], //bg, three walls, guy, a few cars
             vec![SheetRegion::zeroed(); 1],
             camera,
         );
 
+        // ui sprite group, resized each frame to fit the widgets
+        let ui_group = engine.renderer.sprites.add_sprite_group(
+            &engine.renderer.gpu,
+            engine.assets.texture(ui_tex),
+            vec![Transform::zeroed(); 1],
+            vec![SheetRegion::zeroed(); 1],
+            camera,
+        );
+
+        // transition overlay group, last so it draws over everything
+        let fade = transition::add_color_group(&mut engine.renderer, [0, 0, 0, 255], camera);
+        // the console's text goes over even the transitions
+        let dev_text = DevText::new(engine, ui_tex, UiSkin::ascii().font, camera);
+
         let bus = Sprite {
             pos: Vec2 {
                 x: 378.66,
@@ -183,17 +527,62 @@ impl engine::Game for Game {
             size: Vec2 { x: 288.0, y: H },
         };
 
-        let font = engine::BitFont::with_sheet_region(
+        let font = engine::text::Font::with_sheet_region(
             '0'..='9',
             SheetRegion::new(0, 0, 512, 0, 80, 8),
             10,
         );
-        let font_end = engine::BitFont::with_sheet_region(
+        let font_end = engine::text::Font::with_sheet_region(
             '0'..='9',
             SheetRegion::new(0, 0, 866, 0, 80, 8),
             10,
         );
 
+        let mut hud = Hud::new(font.clone());
+        // outlines in the green of the grass
+        let debug = DebugOverlay::new(font, SheetRegion::new(0, 660, 100, 0, 4, 4));
+        let score_text = hud.add(HudElement::text(
+            Anchor::TopLeft,
+            Vec2 { x: 16.0, y: -16.0 },
+            "0",
+            TextStyle::sized(16.0),
+        ));
+        let level_text = hud.add(
+            HudElement::text(
+                Anchor::Top,
+                Vec2 { x: 0.0, y: -16.0 },
+                "1",
+                TextStyle::sized(16.0),
+            )
+            .pivot(Anchor::Top),
+        );
+        // passenger counter frame along the right edge, with a seat for each passenger
+        hud.add(
+            HudElement::sprite(
+                Anchor::TopRight,
+                Vec2 { x: -40.0, y: -300.0 },
+                Vec2 { x: 60.0, y: 500.0 },
+                SheetRegion::new(0, 312, 501, 1, 40, 309),
+            )
+            .pivot(Anchor::Center),
+        );
+        let bus_seats = (0..5)
+            .map(|seat| {
+                let mut element = HudElement::sprite(
+                    Anchor::TopRight,
+                    Vec2 {
+                        x: -40.0,
+                        y: -120.0 - 90.0 * seat as f32,
+                    },
+                    Vec2 { x: 38.4, y: 65.33 },
+                    SheetRegion::zeroed(),
+                )
+                .pivot(Anchor::Center);
+                element.visible = false;
+                hud.add(element)
+            })
+            .collect();
+
         let mut pavements = Vec::with_capacity(34);
         // right pavement
         pavements.push(Sprite {
@@ -205,48 +594,131 @@ impl engine::Game for Game {
             pos: Vec2 { x: 2.0, y: 0.0 },
             vel: Vec2 { x: 0.0, y: -1.0 },
         });
-        let animal_speed_multiplier = 1.0;
-        let building_speed_multiplier = 1.0;
-
         let on_bus: Vec<Person> = Vec::with_capacity(5);
 
+        // animals and people appear anywhere on the road, buildings beside it
+        let jobs = [Job::Doctor, Job::Firefighter, Job::Regular, Job::Cop];
+        let mut spawner = Spawner::default();
+        let rule = |kinds: Vec<(Thing, f32)>, max_live| {
+            let thing = &kinds[0].0;
+            let (placement, interval) = (tuning.placement(thing), tuning.interval(thing));
+            SpawnRule::new(kinds, placement, H + 8.0)
+                .interval(interval)
+                .min_spacing(tuning.collision_distance)
+                .max_live(max_live)
+        };
+        let animal_rule = spawner.add(rule(
+            vec![
+                (Thing::Animal(CatDog::Cat), 1.0),
+                (Thing::Animal(CatDog::Dog), 0.0),
+            ],
+            32,
+        ));
+        spawner.add(rule(
+            jobs.iter().map(|job| (Thing::Person(job.clone()), 1.0)).collect(),
+            5,
+        ));
+        spawner.add(rule(
+            jobs.iter().map(|job| (Thing::Building(job.clone()), 1.0)).collect(),
+            32,
+        ));
+
+        // subscribers run in this order for each event
+        let mut events = EventBus::default();
+        events.subscribe(Game::keep_score);
+        events.subscribe(Game::play_sounds);
+        events.subscribe(Game::show_effects);
+        events.subscribe(Game::end_run);
+        events.subscribe(Game::print_stats);
+
         // drop sound
-        // Create an audio manager
-        let audio_manager =
-            AudioManager::<DefaultBackend>::new(AudioManagerSettings::default()).unwrap();
+        // Open the audio device; pausing the game pauses its sound
+        let audio = Audio::open();
         // Load the drop sound
         let drop_sound = StaticSoundData::from_file(
             "../content/hotel-bell-ding.mp3",
             StaticSoundSettings::default(),
         )
         .unwrap();
+        // a new level every 5 passengers dropped off; faster roads, more
+        // animals and more dogs as the score goes up
+        let difficulty = Difficulty::new(Measure::Score, 5.0, 9);
+        let speed_curve = Curve::new(vec![(0.0, 1.0), (10.0, 1.5), (30.0, 2.2)]).eased(Ease::QuadInOut);
+        let animal_rate_curve = Curve::ramp(1.0, 1.6, 30.0);
+        let dog_weight_curve = Curve::new(vec![(0.0, 0.0), (5.0, 0.5), (20.0, 1.0)]);
+
+        // confetti cut from the grass for drop-offs, dust from the walls for crashes
+        let rng = GameRng::from_entropy();
+        let mut particles = Particles::new(rng.seed());
+        let drop_off_burst = particles.add(
+            Emitter::new(vec![
+                SheetRegion::new(0, 660, 100, 0, 4, 4),
+                SheetRegion::new(0, 325, 600, 0, 4, 4),
+            ])
+            .lifetime(20..40)
+            .speed(100.0..200.0)
+            .gravity(Vec2 { x: 0.0, y: -300.0 })
+            .size(Vec2::splat(10.0), Vec2::splat(4.0), Ease::Linear),
+        );
+        let crash_dust = particles.add(
+            Emitter::new(vec![SheetRegion::new(0, 0, 480, 0, 8, 8)])
+                .lifetime(40..70)
+                .speed(30.0..90.0)
+                .size(Vec2::splat(6.0), Vec2::splat(28.0), Ease::QuadOut),
+        );
+
         // Load the cat sound
         // let cat_sound = StaticSoundData::from_file("../content/angry_cat.mp3", StaticSoundSettings::default()).unwrap();
 
+        // the street fills the screen, so the view only moves when it shakes
+        let world = Rect {
+            corner: Vec2::ZERO,
+            size: Vec2 { x: W, y: H },
+        };
+        let camera = CameraController::new(world.size).bounds(world).smoothing(0.1);
+
         Game {
+            tuning,
             camera,
             bus,
             walls: vec![left_wall, right_wall, floor],
-            animals: Vec::with_capacity(8),
-            people: Vec::with_capacity(30),
-            animal_timer: 0,
-            people_timer: 0,
-            buildings: Vec::with_capacity(33),
-            building_timer: 0,
-            animal_speed_multiplier,
-            building_speed_multiplier,
+            things: World::default(),
+            spawner,
+            animal_rule,
+            difficulty,
+            speed_curve,
+            animal_rate_curve,
+            dog_weight_curve,
             pavements,
             score: 0,
-            font,
+            hud,
+            score_text,
+            level_text,
+            bus_seats,
             font_end,
             curr_frame: 0,
             frame_counter: 0,
             frame_direction: 1,
             game_over: false,
-            audio_manager,
+            audio,
             drop_sound,
             // cat_sound,
             game_state: GameState::TitleScreen,
+            start_group,
+            end_group,
+            ui: Ui::new(UiSkin::ascii()),
+            ui_group,
+            transition: None,
+            next_state: None,
+            fade,
+            particles,
+            debug,
+            dev_text,
+            drop_off_burst,
+            crash_dust,
+            events,
+            rng,
+            high_scores: HighScores::load(<Self as Snapshot>::NAME),
             on_bus,
         }
     }
@@ -255,18 +727,73 @@ impl engine::Game for Game {
         self.game_over
     }
 
-    fn update(&mut self, engine: &mut Engine, acc: f32) {
+    fn update(&mut self, engine: &mut Engine, _acc: f32) {
+        self.reload_tuning(engine);
+        // keep shaking and the dust settling while the scene is frozen under a transition
+        self.camera.tick();
+        engine.profiler.begin("particles");
+        self.particles.update();
+        engine.profiler.end();
+        if let Some(transition) = &mut self.transition {
+            let switch = transition.tick();
+            let done = transition.is_done();
+            if switch {
+                if let Some(next_state) = self.next_state.take() {
+                    if let GameState::GameOver = next_state {
+                        hide_sprite_group(&mut engine.renderer.sprites, &engine.renderer.gpu, 0);
+                    }
+                    self.game_state = next_state;
+                }
+            }
+            if done {
+                // after the crossfade this hides the title screen
+                transition.clear(&mut engine.renderer.sprites, &engine.renderer.gpu);
+                self.transition = None;
+            }
+            // hold the scene still while it is being covered
+            if self.next_state.is_some() {
+                return;
+            }
+        }
         match self.game_state {
             GameState::TitleScreen => {
-                // Check if the space bar is pressed
-                if engine.input.is_key_pressed(engine::Key::Space) {
-                    engine.renderer.sprites.remove_sprite_group(1);
-                    // Transition to the in-game state
+                // Check if the space bar is pressed or the screen tapped
+                if engine.input.is_key_pressed(engine::Key::Space) || engine.pointer.is_tapped() {
+                    // Transition to the in-game state, dissolving the title screen away
                     self.game_state = GameState::InGame;
+                    self.transition = Some(Transition::crossfade(
+                        Overlay {
+                            group: self.start_group,
+                            cover: START_SPRITE_UV,
+                        },
+                        45,
+                    ));
                 }
             }
 
             GameState::InGame => {
+                // quick save and load
+                if engine.input.is_key_pressed(engine::Key::F5) {
+                    if let Err(err) = snapshot::save(self, &snapshot::default_path::<Self>()) {
+                        log::error!(target: "save", "could not save: {err}");
+                    }
+                } else if engine.input.is_key_pressed(engine::Key::F9) {
+                    if let Err(err) = snapshot::load(self, engine, &snapshot::default_path::<Self>()) {
+                        log::error!(target: "save", "could not load: {err}");
+                    }
+                }
+                self.run_commands(engine);
+                self.camera.follow(self.bus.pos);
+                self.difficulty.tick();
+                self.difficulty.set_score(self.score);
+                let spawn_rate = engine.console.get::<f32>("spawn_rate").unwrap_or(1.0);
+                for rule in self.spawner.rules.iter_mut() {
+                    rule.rate = spawn_rate;
+                }
+                let animals = &mut self.spawner.rules[self.animal_rule];
+                animals.rate *= self.difficulty.sample(&self.animal_rate_curve);
+                animals.kinds[1].1 = self.difficulty.sample(&self.dog_weight_curve);
+
                 // set the speed of animation for bus. Adjust number after modulo.
                 self.frame_counter = (self.frame_counter + 1) % 5;
                 if self.frame_counter == 0 {
@@ -278,18 +805,20 @@ impl engine::Game for Game {
                         self.frame_direction *= -1;
                     }
                 }
-                // column values
-                // let possible_values = [261.33, 378.66, 496.0];
-                let side_values = [100.0, W - 100.0];
-
                 // for continuous left or right movement
+                let bus_speed = engine.console.get::<f32>("bus_speed").unwrap_or(self.tuning.bus_speed);
                 let dir = engine.input.key_axis(engine::Key::Left, engine::Key::Right);
-                self.bus.pos.x += dir * GUY_SPEED;
+                self.bus.pos.x += dir * bus_speed;
 
                 // for continuous up or down movement
                 let dir = engine.input.key_axis(engine::Key::Down, engine::Key::Up);
-                self.bus.pos.y += dir * GUY_SPEED;
-                self.bus.pos.y += dir * GUY_SPEED;
+                self.bus.pos.y += dir * self.tuning.bus_vertical_speed;
+
+                // or drive towards wherever the screen is held
+                if let Some(target) = engine.pointer.held_world(&engine.viewport, self.camera.camera()) {
+                    let offset = target - self.bus.pos;
+                    self.bus.pos += offset.clamp_length_max(bus_speed * 2.0);
+                }
 
                 // handle_collisions(self.walls, self.bus, COLLISION_STEPS);
                 let mut contacts = Vec::with_capacity(self.walls.len());
@@ -340,243 +869,174 @@ impl engine::Game for Game {
                         }
                     }
                 }
-                let mut rng = rand::thread_rng();
-
-                // create columns for animals
-                let x_range_animals = (150.0, W - 150.0);
-
-                // spawn new animals
-                if self.animal_timer > 0 {
-                    self.animal_timer -= 1;
-                } else if self.animals.len() < 32 {
-                    let mut valid_position = false;
-                    let mut new_animal_pos = Vec2::default();
-                    while !valid_position {
-                        new_animal_pos = Vec2 {
-                            x: rand::thread_rng().gen_range(x_range_animals.0..x_range_animals.1),
-                            y: H + 8.0,
-                        };
 
-                        // Check if the new position overlaps with existing animals
-                        valid_position = !self.animals.iter().any(|animal| {
-                            new_animal_pos.distance(animal.pos) <= COLLISION_DISTANCE
-                        }) && !self.buildings.iter().any(|building| {
-                            new_animal_pos.distance(building.pos) <= COLLISION_DISTANCE
-                        });
-                    }
-                    let generated_animal = match rand::thread_rng().gen_range(0..1) {
-                        0 => CatDog::Cat,
-                        1 => CatDog::Dog,
-                        _ => unreachable!(), // Should never happen, just to handle all cases
-                    };
-                    self.animals.push(Animal {
-                        pos: new_animal_pos,
-                        vel: Vec2 { x: 0.0, y: -2.0 },
-                        animal_type: generated_animal,
-                    });
-                    self.animal_timer = rng.gen_range(30..90);
-                }
+                // spawn new animals, people and buildings
+                let radius = self.tuning.collision_distance;
+                self.spawner.update(&mut self.things, &mut self.rng, |world, thing, pos| {
+                    thing.spawn(world, pos, radius)
+                });
                 // update animal velocities every frame
-                for animal in self.animals.iter_mut() {
-                    animal.pos += animal.vel;
-                }
+                self.things.move_all(|t| if t.is_animal() { 1.0 } else { 0.0 });
 
-                // between frames, maintain all the animals on the screen that are above position -8.0
-                self.animals.retain(|animal| animal.pos.y > -8.0);
-
-                let x_range_people = (150.0, W - 150.0);
-                // spawn new people
-                if self.people_timer > 0 {
-                    self.people_timer -= 1;
-                } else if self.people.len() < 5 {
-                    let mut valid_position = false;
-                    let mut new_person_pos = Vec2::default();
-                    while !valid_position {
-                        new_person_pos = Vec2 {
-                            x: rand::thread_rng().gen_range(x_range_people.0..x_range_people.1),
-                            y: H + 8.0,
-                        };
-
-                        // Check if the new position overlaps with existing animals
-                        valid_position = !self.animals.iter().any(|animal| {
-                            new_person_pos.distance(animal.pos) <= COLLISION_DISTANCE
-                        }) && !self.buildings.iter().any(|building| {
-                            new_person_pos.distance(building.pos) <= COLLISION_DISTANCE
-                        });
-                    }
-                    // TODO: generate a random job
-                    let generated_job = match rand::thread_rng().gen_range(0..4) {
-                        0 => Job::Doctor,
-                        1 => Job::Firefighter,
-                        2 => Job::Regular,
-                        3 => Job::Cop,
-                        _ => unreachable!(), // Should never happen, just to handle all cases
-                    };
+                // between frames, maintain everything on the screen that is above position -8.0
+                self.things.cull(|pos| pos.y <= -8.0);
 
-                    self.people.push(Person {
-                        pos: new_person_pos,
-                        vel: Vec2 { x: 0.0, y: -2.0 },
-                        job: generated_job,
-                    });
-                    self.people_timer = rng.gen_range(30..180);
-                }
                 // update people velocities every frame
-                for person in self.people.iter_mut() {
-                    person.pos += person.vel;
-                }
+                self.things.move_all(|t| if t.is_person() { 1.0 } else { 0.0 });
 
                 // Check collision with animals
-                if self
-                    .animals
-                    .iter()
-                    .any(|animal| animal.pos.distance(self.bus.pos) <= COLLISION_DISTANCE)
-                {
+                if !self.things.touching(self.bus.pos, 0.0, Thing::is_animal).is_empty() {
                     // play cat sound
-                    // self.audio_manager.play(self.cat_sound.clone()).unwrap();
-                    println!("Game Over! Your final score: {}", self.score);
-                    engine.renderer.sprites.remove_sprite_group(0);
-                    self.game_state = GameState::GameOver;
+                    // self.audio.play(&self.cat_sound);
+                    let god_mode = engine.console.get::<bool>("god_mode").unwrap_or(false);
+                    if self.transition.is_none() && !god_mode {
+                        self.events.emit(GameEvent::Crashed { pos: self.bus.pos });
+                    }
                 }
 
                 // if any person is within the catch distance of the bus, mark a collision
                 if self.on_bus.len() < 5 {
-                    if let Some(idx) = self
-                        .people
-                        .iter()
-                        .position(|person| person.pos.distance(self.bus.pos) <= COLLISION_DISTANCE)
+                    if let Some(&person) = self
+                        .things
+                        .touching(self.bus.pos, 0.0, Thing::is_person)
+                        .first()
                     {
-                        self.on_bus.push(Person {
-                            pos: Vec2 { x: 0.0, y: 0.0 },
-                            vel: Vec2 { x: 0.0, y: 0.0 },
-                            job: self.people[idx].job.clone(),
-                        });
-                        println!("On Bus: {}", self.on_bus.len());
-                        self.people.swap_remove(idx);
+                        if let Some(Thing::Person(job)) = self.things.kind(person).cloned() {
+                            self.on_bus.push(Person {
+                                pos: Vec2 { x: 0.0, y: 0.0 },
+                                vel: Vec2 { x: 0.0, y: 0.0 },
+                                job: job.clone(),
+                            });
+                            self.events.emit(GameEvent::PassengerBoarded { job });
+                        }
+                        self.things.despawn(person);
                     }
                 }
 
-                self.people.retain(|person| person.pos.y > -8.0);
-                // between frames, maintain all the animals on the screen that are above position -8.0
-                self.animals.retain(|animal| animal.pos.y > -8.0);
+                // between frames, maintain everything on the screen that is above position -8.0
+                self.things.cull(|pos| pos.y <= -8.0);
 
                 // if a building is within the catch distance,
-                if let Some(idx) = self.buildings.iter().position(|building: &Building| {
-                    building.pos.distance(self.bus.pos) <= DROP_OFF_DIST
-                }) {
+                if let Some(&building) = self
+                    .things
+                    .touching(
+                        self.bus.pos,
+                        self.tuning.drop_off_distance - self.tuning.collision_distance,
+                        Thing::is_building,
+                    )
+                    .first()
+                {
+                    let Some(Thing::Building(building_job)) = self.things.kind(building).cloned() else {
+                        unreachable!()
+                    };
                     // check if the job of the building matches the job of a person on the bus
                     // remove person from the bus if dropped off
-                    if self
+                    if let Some(person_idx) = self
                         .on_bus
                         .iter()
-                        .any(|person| person.job == self.buildings[idx].job)
+                        .position(|person| person.job == building_job)
                     {
-                        if let Some(person_idx) = self
-                            .on_bus
-                            .iter()
-                            .position(|person| person.job == self.buildings[idx].job)
-                        {
-                            // play drop sound
-                            self.audio_manager.play(self.drop_sound.clone()).unwrap();
-                            println!("Removed a {} from the bus!", self.buildings[idx].job);
-                            self.on_bus.swap_remove(person_idx);
-                            println!("number of people on bus: {}", self.on_bus.len());
-                            self.score += 1;
-                        }
-                        self.buildings.swap_remove(idx);
-                    }
-                }
-
-                self.buildings.retain(|building| building.pos.y > -8.0);
-
-                // Spawn new buildings
-                if self.building_timer > 0 {
-                    self.building_timer -= 1;
-                } else if self.buildings.len() < 32 {
-                    let mut valid_position = false;
-                    let mut new_building_pos = Vec2::default();
-                    while !valid_position {
-                        let uniform_building = Uniform::new(0, side_values.len());
-                        let random_index_building = rng.sample(uniform_building);
-                        new_building_pos = Vec2 {
-                            x: side_values[random_index_building],
-                            y: H + 8.0,
-                        };
-
-                        // Check if the new position overlaps with existing animals or buildings
-                        valid_position = !self.animals.iter().any(|animal| {
-                            new_building_pos.distance(animal.pos) <= COLLISION_DISTANCE
-                        }) && !self.buildings.iter().any(|building| {
-                            new_building_pos.distance(building.pos) <= COLLISION_DISTANCE
+                        self.on_bus.swap_remove(person_idx);
+                        let pos = self.things.pos(building).unwrap_or(self.bus.pos);
+                        self.events.emit(GameEvent::PassengerDropped {
+                            job: building_job,
+                            pos,
                         });
+                        self.things.despawn(building);
+                        self.things.flush();
                     }
-                    let generated_job = match rand::thread_rng().gen_range(0..4) {
-                        0 => Job::Doctor,
-                        1 => Job::Firefighter,
-                        2 => Job::Regular,
-                        3 => Job::Cop,
-                        _ => unreachable!(), // Should never happen, just to handle all cases
-                    };
-                    self.buildings.push(Building {
-                        pos: new_building_pos,
-                        vel: Vec2 { x: 0.0, y: -2.0 },
-                        job: generated_job,
-                    });
-                    self.building_timer = rng.gen_range(30..90);
-                }
-                // Update buildings
-                for building in self.buildings.iter_mut() {
-                    building.pos += building.vel;
                 }
-                self.buildings.retain(|building| building.pos.y > -8.0);
 
-                generate_scrolling_side(&mut self.pavements, PAVEMENT_SPEED, W, H);
-
-                // Increase speed multipliers over time
-                self.animal_speed_multiplier += 0.001 * acc;
-                self.building_speed_multiplier += 0.001 * acc;
+                // Update buildings
+                self.things.move_all(|t| if t.is_building() { 1.0 } else { 0.0 });
+                self.things.cull(|pos| pos.y <= -8.0);
 
-                // Update animals with increased speed
-                for animal in self.animals.iter_mut() {
-                    animal.pos += animal.vel * self.animal_speed_multiplier;
-                }
+                generate_scrolling_side(&mut self.pavements, self.tuning.pavement_speed, W, H);
 
-                // Update buildings with increased speed
-                for building in self.buildings.iter_mut() {
-                    building.pos += building.vel * self.building_speed_multiplier;
-                }
+                // Update animals and buildings with the speed for the current difficulty
+                let speed = self.difficulty.sample(&self.speed_curve);
+                self.things
+                    .move_all(|t| if t.is_person() { 0.0 } else { speed });
             }
             GameState::GameOver => {
                 // hello
             }
         }
+        // let sound, effects and scoring react to this tick's events
+        engine.profiler.begin("events");
+        EventBus::dispatch(self, |game| &mut game.events);
+        engine.profiler.end();
+    }
+    fn paused(&mut self, engine: &mut Engine, paused: bool) {
+        self.audio.set_paused(paused);
+        if paused {
+            // the menu opens on Resume
+            self.ui.set_focus(0);
+        } else if !matches!(self.game_state, GameState::GameOver) {
+            hide_sprite_group(&mut engine.renderer.sprites, &engine.renderer.gpu, self.ui_group);
+        }
     }
+
     fn render(&mut self, engine: &mut Engine) {
+        // sound follows the time scale; render runs even while time is frozen
+        self.audio.set_rate(engine.time_scale() as f64);
         match self.game_state {
             GameState::TitleScreen => {
                 render_start_sprite(
                     &mut engine.renderer.sprites,
                     &mut engine.renderer.gpu,
-                    self.camera,
+                    self.camera.screen_camera(),
+                    self.start_group,
                     W,
                     H,
                 );
             }
             GameState::InGame => {
-                let score_str = self.score.to_string();
-                let text_len = score_str.len();
+                self.hud.set_text(self.score_text, &self.score.to_string());
+                self.hud
+                    .set_text(self.level_text, &self.difficulty.level().to_string());
+                // fill a seat in the counter frame for each person on the bus
+                for (index, seat) in self.bus_seats.iter().enumerate() {
+                    let element = self.hud.element_mut(*seat);
+                    element.visible = index < self.on_bus.len();
+                    if let (Some(person_on_bus), HudContent::Sprite { uv, .. }) =
+                        (self.on_bus.get(index), &mut element.content)
+                    {
+                        *uv = match person_on_bus.job {
+                            Job::Firefighter => SheetRegion::new(0, 134, 480, 0, 16, 19),
+                            Job::Doctor => SheetRegion::new(0, 212, 480, 0, 14, 17),
+                            Job::Cop => SheetRegion::new(0, 177, 480, 0, 14, 18),
+                            Job::Regular => SheetRegion::new(0, 100, 480, 0, 14, 18),
+                        };
+                    }
+                }
+
+                // the bus hits or picks up a thing when its center comes within
+                // the inner circle, and reaches a building at the outer one
+                self.debug.visible = engine.show_debug;
+                self.debug.clear();
+                self.debug.colliders(&self.things);
+                self.debug.circle(self.bus.pos, self.tuning.collision_distance);
+                self.debug.circle(self.bus.pos, self.tuning.drop_off_distance);
+                self.debug.rect(SPRITE {
+                    center: self.bus.pos,
+                    size: Vec2 { x: 38.4, y: 115.0 },
+                });
+                self.debug.stat("things", self.things.len());
+                self.debug.stat("particles", self.particles.sprite_count());
+                self.debug.engine_stats(engine);
 
                 let sprite_count = self.walls.len()
                     + self.pavements.len()
-                    + self.animals.len()
-                    + self.people.len()
-                    + self.buildings.len()
-                    + self.on_bus.len()
-                    + 4;
+                    + self.things.sprite_count()
+                    + self.particles.sprite_count()
+                    + self.debug.sprite_count()
+                    + 2;
 
                 engine.renderer.sprites.resize_sprite_group(
                     &engine.renderer.gpu,
                     0,
-                    sprite_count + text_len,
+                    sprite_count + self.hud.sprite_count(),
                 );
 
                 let (transforms, uvs) = engine.renderer.sprites.get_sprites_mut(0);
@@ -594,29 +1054,17 @@ impl engine::Game for Game {
 
                 // set walls
                 const WALL_START: usize = 1;
-                let frame_start: usize = WALL_START + self.walls.len();
+                let bus_idx: usize = WALL_START + self.walls.len();
                 for (wall, (transform, uv)) in self.walls.iter().zip(
-                    transforms[WALL_START..frame_start]
+                    transforms[WALL_START..bus_idx]
                         .iter_mut()
-                        .zip(uvs[WALL_START..frame_start].iter_mut()),
+                        .zip(uvs[WALL_START..bus_idx].iter_mut()),
                 ) {
                     *transform = (*wall).into();
                     *uv = SheetRegion::new(0, 0, 480, 12, 8, 8);
                 }
 
-                // set sprite counter frame
-                transforms[frame_start] = SPRITE {
-                    center: Vec2 {
-                        x: W - 40.0,
-                        y: H - 300.0,
-                    },
-                    size: Vec2 { x: 60.0, y: 500.0 },
-                }
-                .into();
-                uvs[frame_start] = SheetRegion::new(0, 312, 501, 1, 40, 309);
-
                 // set bus
-                let bus_idx = frame_start + 1;
                 transforms[bus_idx] = SPRITE {
                     center: self.bus.pos,
                     size: Vec2 { x: 50.0, y: 115.0 },
@@ -639,44 +1087,16 @@ impl engine::Game for Game {
                     *uv = SheetRegion::new(0, 640, 0, 5, 45, 748);
                 }
 
-                // set animal
-                let animal_start = pavement_start + self.pavements.len();
-
-                for (animal, (transform, uv)) in self.animals.iter().zip(
-                    transforms[animal_start..]
-                        .iter_mut()
-                        .zip(uvs[animal_start..].iter_mut()),
-                ) {
-                    *transform = SPRITE {
-                        center: animal.pos,
-                        size: Vec2 { x: 38.4, y: 65.33 },
-                    }
-                    .into();
-                    match animal.animal_type {
-                        CatDog::Cat => {
-                            *uv = SheetRegion::new(0, 113, 564, 3, 27, 29);
-                        }
-                        CatDog::Dog => {
-                            *uv = SheetRegion::new(0, 146, 565, 3, 25, 27);
-                        }
-                    }
-                }
-
-                // set people
-                let people_start = animal_start + self.animals.len();
-
-                for (person, (transform, uv)) in self.people.iter().zip(
-                    transforms[people_start..]
-                        .iter_mut()
-                        .zip(uvs[people_start..].iter_mut()),
-                ) {
-                    *transform = SPRITE {
-                        center: person.pos,
-                        size: Vec2 { x: 38.4, y: 65.33 },
-                    }
-                    .into();
-                    let ones_place = self.curr_frame % 10;
-                    match person.job {
+                // animate people
+                let ones_place = self.curr_frame % 10;
+                for (person, thing) in self.things.kinds.iter() {
+                    let (Thing::Person(job), Some(visual)) =
+                        (thing, self.things.visuals.get_mut(person))
+                    else {
+                        continue;
+                    };
+                    let uv = &mut visual.uv;
+                    match job {
                         Job::Firefighter => {
                             let coords_firefighter = [134, 480, 134, 499, 150, 498];
                             animate_char(&ones_place, uv, 0, coords_firefighter, 0, 16, 19);
@@ -696,90 +1116,80 @@ impl engine::Game for Game {
                     }
                 }
 
-                // set building
-                let building_start = people_start + self.people.len();
-                for (building, (transform, uv)) in self.buildings.iter().zip(
-                    transforms[building_start..]
-                        .iter_mut()
-                        .zip(uvs[building_start..].iter_mut()),
-                ) {
-                    *transform = SPRITE {
-                        center: building.pos,
-                        size: Vec2 { x: 60.0, y: 80.0 },
-                    }
-                    .into();
-                    match building.job {
-                        Job::Firefighter => {
-                            *uv = SheetRegion::new(0, 132, 518, 1, 44, 42);
-                        }
-                        Job::Doctor => {
-                            *uv = SheetRegion::new(0, 212, 528, 1, 30, 30);
-                        }
-                        Job::Cop => {
-                            *uv = SheetRegion::new(0, 176, 530, 1, 30, 28);
-                        }
-                        Job::Regular => {
-                            *uv = SheetRegion::new(0, 97, 528, 1, 32, 33);
-                        }
-                    }
-                }
+                // set animals, people and buildings
+                let things_start = pavement_start + self.pavements.len();
+                let particles_start = things_start
+                    + self.things.write_sprites(
+                        &mut transforms[things_start..],
+                        &mut uvs[things_start..],
+                    );
 
-                let on_bus_start = building_start + self.buildings.len();
-                let bus_seats = vec![H - 120.0, H - 210.0, H - 300.0, H - 390.0, H - 480.0];
-                for (index, (person_on_bus, (transform, uv))) in self
-                    .on_bus
-                    .iter()
-                    .zip(
-                        transforms[on_bus_start..]
-                            .iter_mut()
-                            .zip(uvs[on_bus_start..].iter_mut()),
-                    )
-                    .enumerate()
-                {
-                    *transform = SPRITE {
-                        center: Vec2 {
-                            x: W - 40.0,
-                            y: bus_seats[index],
-                        },
-                        size: Vec2 { x: 38.4, y: 65.33 },
-                    }
-                    .into();
-                    match person_on_bus.job {
-                        Job::Firefighter => {
-                            *uv = SheetRegion::new(0, 134, 480, 0, 16, 19);
-                        }
-                        Job::Doctor => {
-                            *uv = SheetRegion::new(0, 212, 480, 0, 14, 17);
-                        }
-                        Job::Cop => {
-                            *uv = SheetRegion::new(0, 177, 480, 0, 14, 18);
-                        }
-                        Job::Regular => {
-                            *uv = SheetRegion::new(0, 100, 480, 0, 14, 18);
-                        }
-                    }
-                }
+                // set confetti and dust
+                let debug_start = particles_start
+                    + self.particles.write_sprites(
+                        &mut transforms[particles_start..],
+                        &mut uvs[particles_start..],
+                    );
 
-                let sprite_count = on_bus_start + self.on_bus.len();
-                render_game_sprites(&self.font, self.camera, &mut engine.renderer.sprites, sprite_count, score_str, &mut engine.renderer.gpu, H);
+                // set the debug overlay on top of everything
+                let sprite_count = debug_start
+                    + self.debug.write_sprites(
+                        self.camera.camera(),
+                        self.camera.view_size,
+                        &mut transforms[debug_start..],
+                        &mut uvs[debug_start..],
+                    );
+                render_game_sprites(&self.hud, self.camera.camera(), self.camera.view_size, &mut engine.renderer.sprites, sprite_count, &mut engine.renderer.gpu);
             }
             GameState::GameOver => {
-                // the end screen sprite group is now at index 0 after removing the first two groups
-                // self.audio_manager.play(self.cat_sound.clone()).unwrap();
+                // self.audio.play(&self.cat_sound);
                 render_end_sprite(
                     &self.font_end,
                     &mut engine.renderer.sprites,
                     &mut engine.renderer.gpu,
                     self.score,
-                    self.camera,
-                    50.0,
-                    W,
-                    H,
+                    self.camera.screen_camera(),
+                    EndLayout {
+                        group: self.end_group,
+                        font_y_offset: 50.0,
+                        width: W,
+                        height: H,
+                    },
                 );
+                // the pause menu takes the ui group while paused
+                if !engine.is_paused() {
+                    self.ui.begin(&engine.keys);
+                    if end_summary(&mut self.ui, self.score, self.high_scores.top(5), W, H) {
+                        self.game_over = true;
+                    }
+                    self.ui.end(&mut engine.renderer.sprites, &engine.renderer.gpu, self.ui_group);
+                }
+            }
+        }
+        if let Some(transition) = &self.transition {
+            let area = if transition.kind == TransitionKind::Crossfade {
+                start_sprite_area(W, H)
+            } else {
+                self.active_camera().into()
+            };
+            transition.render(&mut engine.renderer.sprites, &engine.renderer.gpu, area);
+        }
+        if engine.is_paused() {
+            self.ui.begin(&engine.keys);
+            match pause_menu(&mut self.ui, W, H) {
+                Some(PauseChoice::Resume) => engine.set_paused(false),
+                Some(PauseChoice::Quit) => self.game_over = true,
+                None => {}
             }
+            let sprites = &mut engine.renderer.sprites;
+            self.ui.end(sprites, &engine.renderer.gpu, self.ui_group);
+            // the menu stays put however the game camera moves
+            sprites.set_camera(&engine.renderer.gpu, self.ui_group, self.camera.screen_camera());
         }
+        self.dev_text.render(engine, self.active_camera(), self.camera.view_size);
     }
 }
 fn main() {
+    engine::logging::init("info");
     Engine::new(winit::window::WindowBuilder::new()).run::<Game>();
 }
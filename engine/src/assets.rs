@@ -0,0 +1,210 @@
+// Images and data files from the game's content directory. Textures loaded
+// here are kept, so that with the `dev` feature the directory is watched and
+// an image changed on disk is uploaded again into the texture its sprite
+// groups already use. Other changed files (configs, tuning data, ...) are
+// listed by `take_changed` for the game to load again itself.
+//
+// An image that changes size can't be swapped in place; that needs a restart.
+// Without the `dev` feature nothing is watched and `poll` does nothing.
+use frenderer::{wgpu, WGPU};
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TextureId(usize);
+
+struct LoadedTexture {
+    // as given to `load_texture`, relative to the content directory
+    name: String,
+    size: (u32, u32),
+    texture: wgpu::Texture,
+}
+
+pub struct Assets {
+    root: PathBuf,
+    textures: Vec<LoadedTexture>,
+    // files changed on disk that aren't textures, relative to `root`
+    changed: Vec<PathBuf>,
+    #[cfg(feature = "dev")]
+    watcher: Option<watch::Watcher>,
+}
+
+impl Assets {
+    // `root` is the content directory, e.g. "../content"
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        Assets {
+            #[cfg(feature = "dev")]
+            watcher: watch::Watcher::new(&root),
+            root,
+            textures: Vec::new(),
+            changed: Vec::new(),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    // where `name` is in the content directory
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.root.join(name)
+    }
+
+    fn read_image(&self, name: &str) -> Result<image::RgbaImage, image::ImageError> {
+        Ok(image::open(self.path(name))?.into_rgba8())
+    }
+
+    // loads an sRGB texture, e.g. for `SpriteRenderer::add_sprite_group`
+    pub fn load_texture(&mut self, gpu: &WGPU, name: &str) -> Result<TextureId, image::ImageError> {
+        let image = self.read_image(name)?;
+        let texture = gpu.create_texture(
+            &image,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            image.dimensions(),
+            Some(name),
+        );
+        self.textures.push(LoadedTexture {
+            name: name.to_string(),
+            size: image.dimensions(),
+            texture,
+        });
+        Ok(TextureId(self.textures.len() - 1))
+    }
+
+    pub fn texture(&self, id: TextureId) -> &wgpu::Texture {
+        &self.textures[id.0].texture
+    }
+
+    // reads a data file from the content directory as text
+    pub fn load_text(&self, name: &str) -> std::io::Result<String> {
+        std::fs::read_to_string(self.path(name))
+    }
+
+    // reads the image for a loaded texture again and uploads it over the old one
+    pub fn reload_texture(&self, gpu: &WGPU, id: TextureId) {
+        let loaded = &self.textures[id.0];
+        let image = match self.read_image(&loaded.name) {
+            Ok(image) => image,
+            Err(err) => {
+                log::warn!(target: "assets", "could not reload {}: {err}", loaded.name);
+                return;
+            }
+        };
+        if image.dimensions() != loaded.size {
+            log::warn!(
+                target: "assets",
+                "{} changed size from {:?} to {:?}, restart to see it",
+                loaded.name,
+                loaded.size,
+                image.dimensions()
+            );
+            return;
+        }
+        let (width, height) = loaded.size;
+        gpu.queue.write_texture(
+            loaded.texture.as_image_copy(),
+            &image,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * width),
+                rows_per_image: Some(height),
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        log::info!(target: "assets", "reloaded {}", loaded.name);
+    }
+
+    // Picks up files changed on disk: textures are uploaded again and other
+    // files are kept for `take_changed`. The engine calls this once a frame.
+    pub fn poll(&mut self, gpu: &WGPU) {
+        #[cfg(feature = "dev")]
+        {
+            let Some(watcher) = &self.watcher else {
+                return;
+            };
+            for path in watcher.changed() {
+                let name = path.to_string_lossy().replace('\\', "/");
+                match self.textures.iter().position(|t| t.name == name) {
+                    Some(idx) => self.reload_texture(gpu, TextureId(idx)),
+                    None if !self.changed.contains(&path) => self.changed.push(path),
+                    None => (),
+                }
+            }
+        }
+        #[cfg(not(feature = "dev"))]
+        let _ = gpu;
+    }
+
+    // data files changed since the last call, relative to the content directory
+    pub fn take_changed(&mut self) -> Vec<PathBuf> {
+        std::mem::take(&mut self.changed)
+    }
+}
+
+#[cfg(feature = "dev")]
+mod watch {
+    use notify::{EventKind, RecursiveMode, Watcher as _};
+    use std::path::{Path, PathBuf};
+    use std::sync::mpsc::{channel, Receiver};
+
+    pub struct Watcher {
+        root: PathBuf,
+        // dropping it stops the watching
+        _watcher: notify::RecommendedWatcher,
+        events: Receiver<notify::Result<notify::Event>>,
+    }
+
+    impl Watcher {
+        pub fn new(root: &Path) -> Option<Self> {
+            let start = || -> notify::Result<Self> {
+                let root = root.canonicalize()?;
+                let (tx, events) = channel();
+                let mut watcher = notify::recommended_watcher(tx)?;
+                watcher.watch(&root, RecursiveMode::Recursive)?;
+                Ok(Watcher {
+                    root,
+                    _watcher: watcher,
+                    events,
+                })
+            };
+            match start() {
+                Ok(watcher) => Some(watcher),
+                Err(err) => {
+                    log::warn!(target: "assets", "not watching {}: {err}", root.display());
+                    None
+                }
+            }
+        }
+
+        // files written or created since the last call, relative to the root,
+        // each listed once
+        pub fn changed(&self) -> Vec<PathBuf> {
+            let mut changed = Vec::new();
+            for event in self.events.try_iter() {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(err) => {
+                        log::warn!(target: "assets", "watching failed: {err}");
+                        continue;
+                    }
+                };
+                if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    continue;
+                }
+                for path in event.paths {
+                    let Ok(path) = path.strip_prefix(&self.root).map(Path::to_path_buf) else {
+                        continue;
+                    };
+                    if !changed.contains(&path) {
+                        changed.push(path);
+                    }
+                }
+            }
+            changed
+        }
+    }
+}
@@ -0,0 +1,119 @@
+// Sound playback for games. Wraps kira's audio manager so that a missing audio
+// device leaves the game silent instead of crashing it, so that pausing the
+// game ducks or pauses its sound (see `Game::paused`), and so that sound
+// slows down and speeds up with the engine's time scale.
+use kira::{
+    manager::{backend::DefaultBackend, AudioManager, AudioManagerSettings},
+    sound::{
+        static_sound::{StaticSoundData, StaticSoundHandle},
+        PlaybackState,
+    },
+    tween::Tween,
+};
+use std::time::Duration;
+
+// how long sound takes to duck, pause or come back
+const FADE: Duration = Duration::from_millis(150);
+
+pub struct Audio {
+    manager: Option<AudioManager<DefaultBackend>>,
+    // main volume while the game is paused; 0 pauses playback instead
+    pub duck_volume: f64,
+    paused: bool,
+    // playback rate for every sound, following the time scale
+    rate: f64,
+    // sounds that may still be playing, to change their rate
+    playing: Vec<StaticSoundHandle>,
+}
+
+impl Audio {
+    // opens the default audio device
+    pub fn open() -> Self {
+        let manager = match AudioManager::<DefaultBackend>::new(AudioManagerSettings::default()) {
+            Ok(manager) => Some(manager),
+            Err(err) => {
+                log::warn!("could not open audio, sound is off: {err}");
+                None
+            }
+        };
+        Audio {
+            manager,
+            duck_volume: 0.0,
+            paused: false,
+            rate: 1.0,
+            playing: Vec::new(),
+        }
+    }
+
+    pub fn duck_volume(self, duck_volume: f64) -> Self {
+        Audio {
+            duck_volume,
+            ..self
+        }
+    }
+
+    pub fn play(&mut self, sound: &StaticSoundData) {
+        let Some(manager) = &mut self.manager else {
+            return;
+        };
+        self.playing
+            .retain(|handle| handle.state() != PlaybackState::Stopped);
+        let sound = sound.with_modified_settings(|settings| settings.playback_rate(self.rate));
+        match manager.play(sound) {
+            Ok(handle) => self.playing.push(handle),
+            Err(err) => log::warn!("could not play sound: {err}"),
+        }
+    }
+
+    // sets the playback rate of every sound, playing and to come, e.g. to
+    // `Engine::time_scale`; 0 holds sounds where they are
+    pub fn set_rate(&mut self, rate: f64) {
+        if rate == self.rate {
+            return;
+        }
+        self.rate = rate;
+        let tween = Tween {
+            duration: FADE,
+            ..Default::default()
+        };
+        for handle in self.playing.iter_mut() {
+            if let Err(err) = handle.set_playback_rate(rate, tween) {
+                log::warn!("could not change sound speed: {err}");
+            }
+        }
+    }
+
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    // ducks or pauses everything while the game is paused
+    pub fn set_paused(&mut self, paused: bool) {
+        if paused == self.paused {
+            return;
+        }
+        self.paused = paused;
+        let Some(manager) = &mut self.manager else {
+            return;
+        };
+        let tween = Tween {
+            duration: FADE,
+            ..Default::default()
+        };
+        let result = if self.duck_volume > 0.0 {
+            let volume = if paused { self.duck_volume } else { 1.0 };
+            manager.main_track().set_volume(volume, tween)
+        } else if paused {
+            manager.pause(tween)
+        } else {
+            manager.resume(tween)
+        };
+        if let Err(err) = result {
+            log::warn!("could not change audio: {err}");
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+}
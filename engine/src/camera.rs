@@ -0,0 +1,160 @@
+// A camera controller for gameplay: it follows a target with a deadzone and
+// smoothing, stays inside the world bounds, zooms, and shakes with "trauma"
+// that builds up on hits and wears off over time. Title screens, end screens
+// and the HUD use the fixed `screen_camera` instead.
+use crate::coords;
+use crate::geom::Rect;
+use crate::DT;
+use frenderer::{Camera2D, Transform};
+use glam::*;
+
+#[derive(Clone, Debug)]
+pub struct CameraController {
+    // size of the view at zoom 1, usually the game's logical resolution
+    pub view_size: Vec2,
+    // world point at the center of the view
+    pub center: Vec2,
+    // above 1 zooms in, below 1 zooms out
+    pub zoom: f32,
+    // half size of the box around the center that a followed target can move
+    // in without moving the camera
+    pub deadzone: Vec2,
+    // fraction of the distance to the target covered each tick; 1 snaps
+    pub smoothing: f32,
+    // the view is kept inside these world bounds
+    pub bounds: Option<Rect>,
+    // trauma lost per second
+    pub trauma_decay: f32,
+    // largest shake offset, in world units
+    pub max_shake: f32,
+    trauma: f32,
+    ticks: u32,
+}
+
+impl CameraController {
+    // looking at the area from (0,0) to `view_size`
+    pub fn new(view_size: Vec2) -> Self {
+        CameraController {
+            view_size,
+            center: view_size / 2.0,
+            zoom: 1.0,
+            deadzone: Vec2::ZERO,
+            smoothing: 1.0,
+            bounds: None,
+            trauma_decay: 1.0,
+            max_shake: 16.0,
+            trauma: 0.0,
+            ticks: 0,
+        }
+    }
+
+    pub fn deadzone(self, deadzone: Vec2) -> Self {
+        CameraController { deadzone, ..self }
+    }
+
+    pub fn smoothing(self, smoothing: f32) -> Self {
+        CameraController {
+            smoothing: smoothing.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    pub fn bounds(self, bounds: Rect) -> Self {
+        CameraController {
+            bounds: Some(bounds),
+            ..self
+        }
+    }
+
+    // moves towards `target` once it leaves the deadzone; call once per tick
+    pub fn follow(&mut self, target: Vec2) {
+        let offset = target - self.center;
+        let outside = offset - offset.clamp(-self.deadzone, self.deadzone);
+        self.center += outside * self.smoothing;
+    }
+
+    // adds shake, e.g. 0.3 for a bump and 1.0 for a crash; trauma is capped at 1
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    // advances the shake; call once per tick
+    pub fn tick(&mut self) {
+        self.ticks = self.ticks.wrapping_add(1);
+        self.trauma = (self.trauma - self.trauma_decay * DT).max(0.0);
+    }
+
+    // the visible world area without shake, clamped to the bounds
+    pub fn view(&self) -> Rect {
+        let size = self.view_size / self.zoom.max(f32::EPSILON);
+        let mut corner = self.center - size / 2.0;
+        if let Some(bounds) = self.bounds {
+            let max = bounds.corner + bounds.size - size;
+            // a view bigger than the bounds stays centered on them
+            corner.x = if max.x < bounds.corner.x {
+                bounds.corner.x + (bounds.size.x - size.x) / 2.0
+            } else {
+                corner.x.clamp(bounds.corner.x, max.x)
+            };
+            corner.y = if max.y < bounds.corner.y {
+                bounds.corner.y + (bounds.size.y - size.y) / 2.0
+            } else {
+                corner.y.clamp(bounds.corner.y, max.y)
+            };
+        }
+        Rect { corner, size }
+    }
+
+    // Shake grows with the square of trauma, so small hits barely move the
+    // view. Sums of sines at unrelated frequencies stand in for noise, which
+    // keeps the shake the same from run to run.
+    pub fn shake_offset(&self) -> Vec2 {
+        let amount = self.trauma * self.trauma * self.max_shake;
+        let t = self.ticks as f32 * DT;
+        Vec2 {
+            x: (t * 47.0).sin() * 0.6 + (t * 83.0 + 1.3).sin() * 0.4,
+            y: (t * 53.0 + 0.7).sin() * 0.6 + (t * 71.0 + 2.1).sin() * 0.4,
+        } * amount
+    }
+
+    // the camera for gameplay sprite groups
+    pub fn camera(&self) -> Camera2D {
+        let view = self.view();
+        Rect {
+            corner: view.corner + self.shake_offset(),
+            size: view.size,
+        }
+        .into()
+    }
+
+    // a fixed camera showing (0,0) to `view_size`, for screens and overlays
+    pub fn screen_camera(&self) -> Camera2D {
+        Rect {
+            corner: Vec2::ZERO,
+            size: self.view_size,
+        }
+        .into()
+    }
+}
+
+// Moves sprites laid out on a `screen_size` screen (e.g. the HUD) into the
+// world so they show up in the same place through `camera`, whatever its
+// position and zoom.
+pub fn screen_to_world(camera: Camera2D, screen_size: Vec2, transforms: &mut [Transform]) {
+    let scale = Rect::from(camera).size / screen_size;
+    for transform in transforms.iter_mut() {
+        let pos = Vec2 {
+            x: transform.x,
+            y: transform.y,
+        };
+        let pos = coords::screen_to_world(camera, screen_size, pos);
+        transform.x = pos.x;
+        transform.y = pos.y;
+        transform.w = (transform.w as f32 * scale.x).round() as u16;
+        transform.h = (transform.h as f32 * scale.y).round() as u16;
+    }
+}
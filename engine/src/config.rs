@@ -0,0 +1,111 @@
+// Game tuning read from TOML files in the content directory, so values can be
+// balanced without touching Rust. A game describes its tunables with a serde
+// struct marked `#[serde(default, deny_unknown_fields)]`: values left out of
+// the file keep their defaults, and a misspelled name is an error rather than
+// silently ignored. `Config::validate` then checks the values make sense.
+//
+// With the `dev` feature the file can be edited while the game runs; the game
+// sees it in `Assets::take_changed` and loads it again.
+use crate::assets::Assets;
+use serde::de::DeserializeOwned;
+use std::fmt::{self, Display};
+use std::ops::Range;
+
+pub trait Config: DeserializeOwned + Default {
+    // adds a problem for every value that is out of range
+    fn validate(&self, _problems: &mut Problems) {}
+}
+
+// what's wrong with a config, one message per value
+#[derive(Clone, Debug, Default)]
+pub struct Problems(Vec<String>);
+
+impl Problems {
+    pub fn add(&mut self, problem: String) {
+        self.0.push(problem);
+    }
+
+    pub fn check(&mut self, ok: bool, problem: impl FnOnce() -> String) {
+        if !ok {
+            self.add(problem());
+        }
+    }
+
+    pub fn positive(&mut self, name: &str, value: f32) {
+        self.check(value > 0.0, || format!("{name} must be more than 0, not {value}"));
+    }
+
+    pub fn at_least(&mut self, name: &str, value: f32, min: f32) {
+        self.check(value >= min, || format!("{name} must be at least {min}, not {value}"));
+    }
+
+    pub fn not_empty<T>(&mut self, name: &str, values: &[T]) {
+        self.check(!values.is_empty(), || format!("{name} must not be empty"));
+    }
+
+    // a range of ticks that starts at 1 or later and doesn't run backwards
+    pub fn ticks(&mut self, name: &str, range: &Range<u32>) {
+        self.check(range.start > 0 && range.start <= range.end, || {
+            format!("{name} must be a range of ticks from 1 up, not {range:?}")
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.0.iter()
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Read(std::io::Error),
+    Parse(toml::de::Error),
+    Invalid(Problems),
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Read(err) => write!(f, "{err}"),
+            ConfigError::Parse(err) => write!(f, "{err}"),
+            ConfigError::Invalid(problems) => {
+                let problems: Vec<&str> = problems.iter().map(String::as_str).collect();
+                write!(f, "{}", problems.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+pub fn parse<T: Config>(text: &str) -> Result<T, ConfigError> {
+    let config: T = toml::from_str(text).map_err(ConfigError::Parse)?;
+    let mut problems = Problems::default();
+    config.validate(&mut problems);
+    if problems.is_empty() {
+        Ok(config)
+    } else {
+        Err(ConfigError::Invalid(problems))
+    }
+}
+
+// reads and checks `name` from the content directory
+pub fn load<T: Config>(assets: &Assets, name: &str) -> Result<T, ConfigError> {
+    parse(&assets.load_text(name).map_err(ConfigError::Read)?)
+}
+
+// Like `load`, but falls back to the defaults: quietly when there is no file,
+// and with the errors logged when the file can't be used.
+pub fn load_or_default<T: Config>(assets: &Assets, name: &str) -> T {
+    match load(assets, name) {
+        Ok(config) => config,
+        Err(ConfigError::Read(err)) if err.kind() == std::io::ErrorKind::NotFound => T::default(),
+        Err(err) => {
+            log::error!(target: "config", "{name}: {err}, using the defaults");
+            T::default()
+        }
+    }
+}
@@ -0,0 +1,482 @@
+// An in-game developer console for tuning without recompiling. Games declare
+// typed console variables (cvars) with defaults and read them where the
+// constants used to be, e.g. `console.get::<f32>("car_speed")`; typing
+// `car_speed 1.5` sets one and `car_speed` shows it. Games also declare
+// commands, which the console hands back from `take_commands` so the game runs
+// them with full access to its own state, like timers from a `Scheduler`.
+//
+// The engine owns the console and feeds it the keyboard while it is open (see
+// `Engine::console_key`), so game keys don't fire while typing. Up and down go
+// through the history and tab completes names. Games draw it with a
+// `devtext::DevText`; output is also logged under the "console" target, so it
+// shows up on the terminal too.
+use crate::camera;
+use crate::text::{Font, TextStyle};
+use frenderer::{input::Key, Camera2D, SheetRegion, Transform};
+use glam::*;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+
+// commands the console runs itself
+const BUILTINS: [&str; 4] = ["clear", "cvars", "help", "reset"];
+// gap between the console and the screen's edge
+const MARGIN: f32 = 8.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    Float(f32),
+    Text(String),
+}
+
+impl Value {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Bool(_) => "bool",
+            Value::Int(_) => "int",
+            Value::Float(_) => "float",
+            Value::Text(_) => "text",
+        }
+    }
+
+    // reads `text` as a value of the same type as this one
+    fn parse_like(&self, text: &str) -> Option<Value> {
+        match self {
+            Value::Bool(_) => match text {
+                "1" | "true" | "on" => Some(Value::Bool(true)),
+                "0" | "false" | "off" => Some(Value::Bool(false)),
+                _ => None,
+            },
+            Value::Int(_) => text.parse().ok().map(Value::Int),
+            Value::Float(_) => text.parse().ok().map(Value::Float),
+            Value::Text(_) => Some(Value::Text(text.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(b) => write!(f, "{b}"),
+            Value::Int(i) => write!(f, "{i}"),
+            Value::Float(x) => write!(f, "{x}"),
+            Value::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+// Rust types a cvar can hold
+pub trait CvarType: Sized {
+    fn into_value(self) -> Value;
+    fn from_value(value: &Value) -> Option<Self>;
+}
+
+impl CvarType for bool {
+    fn into_value(self) -> Value {
+        Value::Bool(self)
+    }
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+}
+
+impl CvarType for i64 {
+    fn into_value(self) -> Value {
+        Value::Int(self)
+    }
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+}
+
+impl CvarType for f32 {
+    fn into_value(self) -> Value {
+        Value::Float(self)
+    }
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Float(x) => Some(*x),
+            _ => None,
+        }
+    }
+}
+
+impl CvarType for String {
+    fn into_value(self) -> Value {
+        Value::Text(self)
+    }
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Text(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Cvar {
+    value: Value,
+    default: Value,
+    help: &'static str,
+}
+
+// a game command typed into the console
+#[derive(Clone, Debug, PartialEq)]
+pub struct Command {
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Console {
+    // lines of output kept, and shown when drawn
+    pub output_len: usize,
+    // submitted lines kept for going back through
+    pub history_len: usize,
+    open: bool,
+    cvars: BTreeMap<String, Cvar>,
+    // game commands and their help
+    commands: BTreeMap<String, &'static str>,
+    line: String,
+    history: Vec<String>,
+    // position in `history` while going back through it
+    history_pos: Option<usize>,
+    output: VecDeque<String>,
+    pending: Vec<Command>,
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Console {
+            output_len: 12,
+            history_len: 32,
+            open: false,
+            cvars: BTreeMap::new(),
+            commands: BTreeMap::new(),
+            line: String::new(),
+            history: Vec::new(),
+            history_pos: None,
+            output: VecDeque::new(),
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl Console {
+    // declares a cvar; declaring it again keeps its value if the type matches
+    pub fn cvar<T: CvarType>(&mut self, name: &str, default: T, help: &'static str) {
+        let default = default.into_value();
+        let value = match self.cvars.get(name) {
+            Some(cvar) if cvar.value.type_name() == default.type_name() => cvar.value.clone(),
+            _ => default.clone(),
+        };
+        self.cvars.insert(
+            name.to_string(),
+            Cvar {
+                value,
+                default,
+                help,
+            },
+        );
+    }
+
+    // None if there is no such cvar or it holds another type
+    pub fn get<T: CvarType>(&self, name: &str) -> Option<T> {
+        self.cvars.get(name).and_then(|cvar| T::from_value(&cvar.value))
+    }
+
+    // returns false if there is no such cvar or it holds another type
+    pub fn set<T: CvarType>(&mut self, name: &str, value: T) -> bool {
+        let value = value.into_value();
+        match self.cvars.get_mut(name) {
+            Some(cvar) if cvar.value.type_name() == value.type_name() => {
+                cvar.value = value;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // declares a game command, for `take_commands`, help and completion
+    pub fn command(&mut self, name: &str, help: &'static str) {
+        self.commands.insert(name.to_string(), help);
+    }
+
+    // game commands submitted since the last call, oldest first
+    pub fn take_commands(&mut self) -> Vec<Command> {
+        std::mem::take(&mut self.pending)
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+        self.history_pos = None;
+    }
+
+    pub fn toggle(&mut self) {
+        self.set_open(!self.open);
+    }
+
+    // the line being typed
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    // oldest first
+    pub fn output(&self) -> impl Iterator<Item = &String> {
+        self.output.iter()
+    }
+
+    pub fn print(&mut self, text: &str) {
+        for line in text.lines() {
+            log::info!(target: "console", "{line}");
+            self.output.push_back(line.to_string());
+        }
+        while self.output.len() > self.output_len {
+            self.output.pop_front();
+        }
+    }
+
+    pub fn type_char(&mut self, c: char) {
+        if !c.is_control() {
+            self.line.push(c);
+        }
+    }
+
+    // line editing, history and completion
+    pub fn key(&mut self, key: Key) {
+        match key {
+            Key::Return | Key::NumpadEnter => {
+                let line = std::mem::take(&mut self.line);
+                self.submit(&line);
+            }
+            Key::Back => {
+                self.line.pop();
+            }
+            Key::Escape => self.set_open(false),
+            Key::Up => self.history_step(true),
+            Key::Down => self.history_step(false),
+            Key::Tab => self.complete(),
+            _ => (),
+        }
+    }
+
+    fn history_step(&mut self, back: bool) {
+        if self.history.is_empty() {
+            return;
+        }
+        self.history_pos = match (self.history_pos, back) {
+            (None, true) => Some(self.history.len() - 1),
+            (None, false) => None,
+            (Some(pos), true) => Some(pos.saturating_sub(1)),
+            (Some(pos), false) if pos + 1 < self.history.len() => Some(pos + 1),
+            (Some(_), false) => None,
+        };
+        self.line = self
+            .history_pos
+            .map(|pos| self.history[pos].clone())
+            .unwrap_or_default();
+    }
+
+    // names starting with `prefix`: commands and cvars for the first word,
+    // cvars after it (for `reset` and `help`)
+    pub fn completions(&self, prefix: &str, first_word: bool) -> Vec<&str> {
+        let cvars = self.cvars.keys().map(String::as_str);
+        let mut names: Vec<&str> = if first_word {
+            BUILTINS
+                .into_iter()
+                .chain(self.commands.keys().map(String::as_str))
+                .chain(cvars)
+                .collect()
+        } else {
+            cvars.collect()
+        };
+        names.retain(|name| name.starts_with(prefix));
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    // completes the last word as far as it is unambiguous, and lists the
+    // choices when there is more than one
+    fn complete(&mut self) {
+        let start = self.line.rfind(' ').map_or(0, |i| i + 1);
+        let word = &self.line[start..];
+        let matches = self.completions(word, start == 0);
+        let Some(first) = matches.first() else {
+            return;
+        };
+        let common = matches.iter().fold(first.to_string(), |common, name| {
+            common
+                .chars()
+                .zip(name.chars())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect()
+        });
+        let mut line = format!("{}{common}", &self.line[..start]);
+        if matches.len() == 1 {
+            line.push(' ');
+        }
+        let choices = (matches.len() > 1).then(|| matches.join(" "));
+        self.line = line;
+        if let Some(choices) = choices {
+            self.print(&choices);
+        }
+    }
+
+    // runs a line as if it were typed
+    pub fn submit(&mut self, line: &str) {
+        let line = line.trim();
+        self.history_pos = None;
+        if line.is_empty() {
+            return;
+        }
+        if self.history.last().map(String::as_str) != Some(line) {
+            self.history.push(line.to_string());
+            if self.history.len() > self.history_len {
+                self.history.remove(0);
+            }
+        }
+        self.print(&format!("> {line}"));
+        let mut words = line.split_whitespace();
+        let Some(name) = words.next() else {
+            return;
+        };
+        let args: Vec<String> = words.map(str::to_string).collect();
+        match name {
+            "clear" => self.output.clear(),
+            "cvars" => {
+                let list: Vec<String> = self
+                    .cvars
+                    .iter()
+                    .map(|(name, cvar)| format!("{name} = {}", cvar.value))
+                    .collect();
+                self.print(&list.join("\n"));
+            }
+            "help" => self.help(args.first().map(String::as_str)),
+            "reset" => match args.first() {
+                Some(name) => match self.cvars.get_mut(name) {
+                    Some(cvar) => {
+                        cvar.value = cvar.default.clone();
+                        let text = format!("{name} = {}", cvar.value);
+                        self.print(&text);
+                    }
+                    None => self.print(&format!("no cvar {name}")),
+                },
+                None => {
+                    self.cvars
+                        .values_mut()
+                        .for_each(|cvar| cvar.value = cvar.default.clone());
+                    self.print("all cvars reset");
+                }
+            },
+            _ if self.cvars.contains_key(name) => self.run_cvar(name, &args),
+            _ if self.commands.contains_key(name) => self.pending.push(Command {
+                name: name.to_string(),
+                args,
+            }),
+            _ => self.print(&format!("unknown command {name}, try help")),
+        }
+    }
+
+    // shows a cvar, or sets it from the first argument
+    fn run_cvar(&mut self, name: &str, args: &[String]) {
+        let Some(cvar) = self.cvars.get_mut(name) else {
+            return;
+        };
+        let text = match args.first() {
+            None => format!("{name} = {}", cvar.value),
+            Some(arg) => match cvar.value.parse_like(arg) {
+                Some(value) => {
+                    cvar.value = value;
+                    format!("{name} = {}", cvar.value)
+                }
+                None => format!("{name} takes a {}", cvar.value.type_name()),
+            },
+        };
+        self.print(&text);
+    }
+
+    fn help(&mut self, topic: Option<&str>) {
+        let text = match topic {
+            Some(name) => match (self.cvars.get(name), self.commands.get(name)) {
+                (Some(cvar), _) => format!(
+                    "{name} ({}, default {}): {}",
+                    cvar.value.type_name(),
+                    cvar.default,
+                    cvar.help
+                ),
+                (None, Some(help)) => format!("{name}: {help}"),
+                (None, None) => format!("no command or cvar {name}"),
+            },
+            None => {
+                let commands: Vec<&str> = BUILTINS
+                    .into_iter()
+                    .chain(self.commands.keys().map(String::as_str))
+                    .collect();
+                format!(
+                    "commands: {}\ntype a cvar's name to see it, or a name and a value to set it\nhelp <name> explains one",
+                    commands.join(" ")
+                )
+            }
+        };
+        self.print(&text);
+    }
+
+    fn text(&self) -> String {
+        let mut lines: Vec<&str> = self.output.iter().map(String::as_str).collect();
+        let prompt = format!("> {}_", self.line);
+        lines.push(&prompt);
+        lines.join("\n")
+    }
+
+    // sprites needed to draw the console with `font` while it is open
+    pub fn sprite_count(&self, font: &Font) -> usize {
+        if self.open {
+            font.sprite_count(&self.text())
+        } else {
+            0
+        }
+    }
+
+    // Writes the output and the line being typed in the top left of a
+    // `screen_size` screen seen through `camera`, if the console is open;
+    // returns how many sprites were written. It needs a font with letters,
+    // e.g. `Font::ascii`.
+    pub fn write_sprites(
+        &self,
+        font: &Font,
+        style: &TextStyle,
+        camera: Camera2D,
+        screen_size: Vec2,
+        transforms: &mut [Transform],
+        uvs: &mut [SheetRegion],
+    ) -> usize {
+        if !self.open {
+            return 0;
+        }
+        let count = font.draw(
+            transforms,
+            uvs,
+            &self.text(),
+            Vec2 {
+                x: MARGIN,
+                y: screen_size.y - MARGIN,
+            },
+            style,
+        );
+        camera::screen_to_world(camera, screen_size, &mut transforms[..count]);
+        count
+    }
+}
@@ -0,0 +1,111 @@
+// Conversions between the three coordinate spaces a game deals with:
+// - window: physical pixels as winit reports them, origin top-left, y down
+// - screen: the game's logical resolution (e.g. 768x1280), origin bottom-left,
+//   y up; menus and the HUD are laid out here
+// - world: game units, seen through a camera
+// Frenderer stretches a camera's view over the whole window, so a window
+// position maps to the screen or the world by its fraction of the window
+// size, whatever the window's current size or DPI scale factor.
+use crate::geom::Rect;
+use frenderer::Camera2D;
+use glam::*;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Viewport {
+    // inner size of the window in physical pixels
+    pub size: Vec2,
+    // physical pixels per logical pixel
+    pub scale_factor: f32,
+}
+
+impl Viewport {
+    pub fn new(size: PhysicalSize<u32>, scale_factor: f64) -> Self {
+        Viewport {
+            size: Vec2 {
+                x: size.width as f32,
+                y: size.height as f32,
+            },
+            scale_factor: scale_factor as f32,
+        }
+    }
+
+    // call on `WindowEvent::Resized`
+    pub fn resize(&mut self, size: PhysicalSize<u32>) {
+        self.size = Vec2 {
+            x: size.width as f32,
+            y: size.height as f32,
+        };
+    }
+
+    // call on `WindowEvent::ScaleFactorChanged`, which also resizes the window
+    pub fn rescale(&mut self, scale_factor: f64, size: PhysicalSize<u32>) {
+        self.scale_factor = scale_factor as f32;
+        self.resize(size);
+    }
+
+    // inner size of the window in logical pixels
+    pub fn logical_size(&self) -> Vec2 {
+        self.size / self.scale_factor
+    }
+
+    // a window position in logical pixels, still top-left origin
+    pub fn physical_to_logical(&self, pos: PhysicalPosition<f64>) -> Vec2 {
+        Vec2 {
+            x: pos.x as f32,
+            y: pos.y as f32,
+        } / self.scale_factor
+    }
+
+    pub fn logical_to_physical(&self, pos: Vec2) -> PhysicalPosition<f64> {
+        let pos = pos * self.scale_factor;
+        PhysicalPosition::new(pos.x as f64, pos.y as f64)
+    }
+
+    // 0 to 1 across the window, origin bottom-left; a minimized window has
+    // no size, so it is treated as 1x1
+    fn window_to_unit(&self, pos: PhysicalPosition<f64>) -> Vec2 {
+        let size = self.size.max(Vec2::ONE);
+        Vec2 {
+            x: pos.x as f32 / size.x,
+            y: 1.0 - pos.y as f32 / size.y,
+        }
+    }
+
+    fn unit_to_window(&self, unit: Vec2) -> PhysicalPosition<f64> {
+        PhysicalPosition::new(
+            (unit.x * self.size.x) as f64,
+            ((1.0 - unit.y) * self.size.y) as f64,
+        )
+    }
+
+    pub fn window_to_screen(&self, pos: PhysicalPosition<f64>, screen_size: Vec2) -> Vec2 {
+        self.window_to_unit(pos) * screen_size
+    }
+
+    pub fn screen_to_window(&self, pos: Vec2, screen_size: Vec2) -> PhysicalPosition<f64> {
+        self.unit_to_window(pos / screen_size)
+    }
+
+    // where a window position, e.g. the mouse, lands in the world seen through `camera`
+    pub fn window_to_world(&self, pos: PhysicalPosition<f64>, camera: Camera2D) -> Vec2 {
+        let view = Rect::from(camera);
+        view.corner + self.window_to_unit(pos) * view.size
+    }
+
+    pub fn world_to_window(&self, pos: Vec2, camera: Camera2D) -> PhysicalPosition<f64> {
+        let view = Rect::from(camera);
+        self.unit_to_window((pos - view.corner) / view.size)
+    }
+}
+
+// a point laid out on a `screen_size` screen to where it shows through `camera`
+pub fn screen_to_world(camera: Camera2D, screen_size: Vec2, pos: Vec2) -> Vec2 {
+    let view = Rect::from(camera);
+    view.corner + pos / screen_size * view.size
+}
+
+pub fn world_to_screen(camera: Camera2D, screen_size: Vec2, pos: Vec2) -> Vec2 {
+    let view = Rect::from(camera);
+    (pos - view.corner) / view.size * screen_size
+}
@@ -0,0 +1,231 @@
+// A developer overlay for tuning collisions: outlines of colliders and
+// hitboxes drawn in the world, and a column of numbers (tick rate, frame time,
+// entity counts, sprite group sizes, ...) in the top right of the screen.
+//
+// It draws with ordinary sprites, so the game writes it into its sprite group
+// like anything else. Each frame the game shows or hides it following
+// `Engine::show_debug` (toggled with `Engine::debug_key`), clears it, and
+// queues shapes and stats before writing it; queueing does nothing while it
+// is hidden.
+use crate::camera;
+use crate::ecs::World;
+use crate::geom::{Rect, SPRITE};
+use crate::text::{Align, Font, TextStyle};
+use crate::Engine;
+use frenderer::{SheetRegion, Transform};
+use glam::*;
+use std::fmt::Display;
+
+// dots around each circle outline
+const CIRCLE_DOTS: usize = 16;
+// gap between the stats and the screen's edge
+const MARGIN: f32 = 8.0;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Shape {
+    Circle { center: Vec2, radius: f32 },
+    Rect(Rect),
+}
+
+impl Shape {
+    fn sprite_count(&self) -> usize {
+        match self {
+            Shape::Circle { .. } => CIRCLE_DOTS,
+            Shape::Rect(_) => 4,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DebugOverlay {
+    pub visible: bool,
+    // a small solid-colored region that outlines are drawn with; its depth
+    // should put it in front of the game
+    pub line: SheetRegion,
+    // width of outlines in world units
+    pub thickness: f32,
+    // the stats are drawn with this font; a label is left off when the font
+    // can't draw it, e.g. a digits-only font
+    pub font: Font,
+    pub style: TextStyle,
+    shapes: Vec<Shape>,
+    stats: Vec<String>,
+}
+
+impl DebugOverlay {
+    pub fn new(font: Font, line: SheetRegion) -> Self {
+        DebugOverlay {
+            visible: false,
+            line,
+            thickness: 2.0,
+            font,
+            style: TextStyle::sized(12.0).align(Align::Right),
+            shapes: Vec::new(),
+            stats: Vec::new(),
+        }
+    }
+
+    pub fn thickness(self, thickness: f32) -> Self {
+        DebugOverlay { thickness, ..self }
+    }
+
+    pub fn style(self, style: TextStyle) -> Self {
+        DebugOverlay { style, ..self }
+    }
+
+    pub fn circle(&mut self, center: Vec2, radius: f32) {
+        if self.visible {
+            self.shapes.push(Shape::Circle { center, radius });
+        }
+    }
+
+    // e.g. a `SPRITE` hitbox
+    pub fn rect(&mut self, rect: impl Into<Rect>) {
+        if self.visible {
+            self.shapes.push(Shape::Rect(rect.into()));
+        }
+    }
+
+    // outlines the collider of every entity in `world`
+    pub fn colliders<K>(&mut self, world: &World<K>) {
+        if !self.visible {
+            return;
+        }
+        for (entity, collider) in world.colliders.iter() {
+            if let Some(pos) = world.pos(entity) {
+                self.circle(pos, collider.radius);
+            }
+        }
+    }
+
+    // adds a line to the stats, e.g. `stat("things", things.len())`
+    pub fn stat(&mut self, label: &str, value: impl Display) {
+        if !self.visible {
+            return;
+        }
+        let line = if label.chars().all(|c| self.font.contains(c)) {
+            format!("{label} {value}")
+        } else {
+            value.to_string()
+        };
+        self.stats.push(line);
+    }
+
+    // ticks per second, frame time in milliseconds, ticks in the last frame,
+    // the average microseconds spent updating, rendering and presenting, and
+    // the size of each sprite group, in group order
+    pub fn engine_stats(&mut self, engine: &Engine) {
+        if !self.visible {
+            return;
+        }
+        self.stat("tps", engine.tick_rate().round());
+        self.stat("ms", (engine.frame_time() * 1000.0).round());
+        let profiler = &engine.profiler;
+        if let Some(frame) = profiler.last_frame() {
+            self.stat("ticks", frame.ticks);
+        }
+        for scope in ["update", "render", "present"] {
+            self.stat(scope, profiler.average(scope).as_micros());
+        }
+        let sprites = &engine.renderer.sprites;
+        for group in 0..sprites.sprite_group_count() {
+            self.stat(&format!("group{group}"), sprites.sprite_group_size(group));
+        }
+    }
+
+    // drops the last frame's shapes and stats
+    pub fn clear(&mut self) {
+        self.shapes.clear();
+        self.stats.clear();
+    }
+
+    fn stats_text(&self) -> String {
+        self.stats.join("\n")
+    }
+
+    pub fn sprite_count(&self) -> usize {
+        if !self.visible {
+            return 0;
+        }
+        self.shapes.iter().map(Shape::sprite_count).sum::<usize>()
+            + self.font.sprite_count(&self.stats_text())
+    }
+
+    // Writes the outlines in world space and the stats in the top right of a
+    // `screen_size` screen seen through `camera`; returns how many sprites
+    // were written.
+    pub fn write_sprites(
+        &self,
+        camera: frenderer::Camera2D,
+        screen_size: Vec2,
+        transforms: &mut [Transform],
+        uvs: &mut [SheetRegion],
+    ) -> usize {
+        if !self.visible {
+            return 0;
+        }
+        let mut sprites = Vec::new();
+        for shape in self.shapes.iter() {
+            match *shape {
+                Shape::Circle { center, radius } => {
+                    for i in 0..CIRCLE_DOTS {
+                        let angle = i as f32 / CIRCLE_DOTS as f32 * std::f32::consts::TAU;
+                        sprites.push(SPRITE {
+                            center: center + Vec2::from_angle(angle) * radius,
+                            size: Vec2::splat(self.thickness * 1.5),
+                        });
+                    }
+                }
+                Shape::Rect(rect) => {
+                    let min = rect.corner;
+                    let max = rect.corner + rect.size;
+                    let mid = rect.corner + rect.size / 2.0;
+                    let horizontal = Vec2 {
+                        x: rect.size.x,
+                        y: self.thickness,
+                    };
+                    let vertical = Vec2 {
+                        x: self.thickness,
+                        y: rect.size.y,
+                    };
+                    sprites.extend([
+                        SPRITE {
+                            center: Vec2 { x: mid.x, y: min.y },
+                            size: horizontal,
+                        },
+                        SPRITE {
+                            center: Vec2 { x: mid.x, y: max.y },
+                            size: horizontal,
+                        },
+                        SPRITE {
+                            center: Vec2 { x: min.x, y: mid.y },
+                            size: vertical,
+                        },
+                        SPRITE {
+                            center: Vec2 { x: max.x, y: mid.y },
+                            size: vertical,
+                        },
+                    ]);
+                }
+            }
+        }
+        let mut count = 0;
+        for (sprite, (transform, uv)) in sprites
+            .into_iter()
+            .zip(transforms.iter_mut().zip(uvs.iter_mut()))
+        {
+            *transform = sprite.into();
+            *uv = self.line;
+            count += 1;
+        }
+        let text_len = self.font.draw(
+            &mut transforms[count..],
+            &mut uvs[count..],
+            &self.stats_text(),
+            screen_size - Vec2::splat(MARGIN),
+            &self.style,
+        );
+        camera::screen_to_world(camera, screen_size, &mut transforms[count..count + text_len]);
+        count + text_len
+    }
+}
@@ -0,0 +1,81 @@
+// Developer text drawn over the game: the console while it is open, and the
+// latest log lines while `log_key` has them shown. It needs letters, so it has
+// a sprite group of its own on an ASCII font sheet (e.g. content/ascii.png
+// with `UiSkin::ascii().font`), added after all of the game's groups so it
+// draws over them. Games hide groups rather than removing them, so the group
+// keeps the index it was added at.
+use crate::assets::TextureId;
+use crate::logging::LogView;
+use crate::text::{Font, TextStyle};
+use crate::Engine;
+use bytemuck::Zeroable;
+use frenderer::{input::Key, Camera2D, SheetRegion, Transform};
+use glam::*;
+
+pub struct DevText {
+    pub font: Font,
+    pub console_style: TextStyle,
+    // recent log lines in the bottom left of the screen
+    pub log: LogView,
+    // shows and hides `log`
+    pub log_key: Option<Key>,
+    group: usize,
+}
+
+impl DevText {
+    // adds the text group drawing `font` from the loaded `texture`; call after
+    // adding every other sprite group
+    pub fn new(engine: &mut Engine, texture: TextureId, font: Font, camera: Camera2D) -> Self {
+        let group = engine.renderer.sprites.add_sprite_group(
+            &engine.renderer.gpu,
+            engine.assets.texture(texture),
+            vec![Transform::zeroed(); 1],
+            vec![SheetRegion::zeroed(); 1],
+            camera,
+        );
+        DevText {
+            log: LogView::new(font.clone()),
+            font,
+            console_style: TextStyle::sized(16.0),
+            log_key: Some(Key::F2),
+            group,
+        }
+    }
+
+    pub fn group(&self) -> usize {
+        self.group
+    }
+
+    // Writes the text into its sprite group, laid out on a `screen_size`
+    // screen seen through `camera`; call at the end of `Game::render`.
+    pub fn render(&mut self, engine: &mut Engine, camera: Camera2D, screen_size: Vec2) {
+        if let Some(key) = self.log_key {
+            if engine.keys.is_key_pressed(key) {
+                self.log.visible = !self.log.visible;
+            }
+        }
+        self.log.refresh();
+        let sprites = &mut engine.renderer.sprites;
+        let gpu = &engine.renderer.gpu;
+        let count = engine.console.sprite_count(&self.font) + self.log.sprite_count();
+        sprites.resize_sprite_group(gpu, self.group, count);
+        let (transforms, uvs) = sprites.get_sprites_mut(self.group);
+        let console_len = engine.console.write_sprites(
+            &self.font,
+            &self.console_style,
+            camera,
+            screen_size,
+            transforms,
+            uvs,
+        );
+        let count = console_len
+            + self.log.write_sprites(
+                camera,
+                screen_size,
+                &mut transforms[console_len..],
+                &mut uvs[console_len..],
+            );
+        sprites.set_camera(gpu, self.group, camera);
+        sprites.upload_sprites(gpu, self.group, 0..count);
+    }
+}
@@ -0,0 +1,117 @@
+// Difficulty that ramps up with elapsed game time or score. A game describes
+// each tunable (scroll speed, spawn rate, hazard weights...) as a `Curve` over
+// the difficulty's progress and samples it every tick.
+use crate::ease::{lerp, Ease};
+use crate::DT;
+use serde::{Deserialize, Serialize};
+
+// A piecewise curve through (progress, value) points, eased between each pair.
+// Before the first point and after the last the value is held, so the last
+// point is the curve's cap.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Curve {
+    pub points: Vec<(f32, f32)>,
+    pub ease: Ease,
+}
+
+impl Curve {
+    // points must be sorted by progress
+    pub fn new(points: Vec<(f32, f32)>) -> Self {
+        Curve {
+            points,
+            ease: Ease::Linear,
+        }
+    }
+
+    pub fn constant(value: f32) -> Self {
+        Self::new(vec![(0.0, value)])
+    }
+
+    // from `from` at progress 0 to `to` at `progress`, then held
+    pub fn ramp(from: f32, to: f32, progress: f32) -> Self {
+        Self::new(vec![(0.0, from), (progress, to)])
+    }
+
+    pub fn eased(self, ease: Ease) -> Self {
+        Curve { ease, ..self }
+    }
+
+    pub fn sample(&self, progress: f32) -> f32 {
+        let Some(&(first_x, first_y)) = self.points.first() else {
+            return 0.0;
+        };
+        if progress <= first_x {
+            return first_y;
+        }
+        for pair in self.points.windows(2) {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            if progress < x1 {
+                let t = (progress - x0) / (x1 - x0);
+                return lerp(y0, y1, self.ease.apply(t));
+            }
+        }
+        self.points[self.points.len() - 1].1
+    }
+}
+
+// what difficulty progresses with
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Measure {
+    // seconds of game time, counted in ticks so it doesn't depend on frame rate
+    Time,
+    Score,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Difficulty {
+    pub measure: Measure,
+    // progress needed for each level, e.g. 30.0 for a new level every 30 seconds
+    pub level_every: f32,
+    pub max_level: u32,
+    ticks: u32,
+    score: u32,
+}
+
+impl Difficulty {
+    pub fn new(measure: Measure, level_every: f32, max_level: u32) -> Self {
+        Difficulty {
+            measure,
+            level_every,
+            max_level,
+            ticks: 0,
+            score: 0,
+        }
+    }
+
+    // call once per `Game::update`
+    pub fn tick(&mut self) {
+        self.ticks += 1;
+    }
+
+    pub fn set_score(&mut self, score: u32) {
+        self.score = score;
+    }
+
+    pub fn elapsed(&self) -> f32 {
+        self.ticks as f32 * DT
+    }
+
+    pub fn progress(&self) -> f32 {
+        match self.measure {
+            Measure::Time => self.elapsed(),
+            Measure::Score => self.score as f32,
+        }
+    }
+
+    // starts at 1, for showing in the HUD
+    pub fn level(&self) -> u32 {
+        if self.level_every <= 0.0 {
+            return 1;
+        }
+        ((self.progress() / self.level_every) as u32 + 1).min(self.max_level)
+    }
+
+    pub fn sample(&self, curve: &Curve) -> f32 {
+        curve.sample(self.progress())
+    }
+}
@@ -0,0 +1,330 @@
+// A small entity store for game objects. Every entity has a game-defined kind
+// (e.g. an enum of Car, Coin, ...) and a position, and can have a velocity,
+// a visual and a collider. Games keep any other per-entity data in their own
+// `Components` or in the kind itself.
+use crate::geom;
+use frenderer::{SheetRegion, Transform};
+use glam::*;
+use serde::{Deserialize, Serialize};
+
+// Ids are reused after an entity is despawned, so each carries the generation
+// of its slot; an old id never refers to a newer entity.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+// one optional value per entity slot
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Components<T> {
+    slots: Vec<Option<(u32, T)>>,
+}
+
+impl<T> Default for Components<T> {
+    fn default() -> Self {
+        Components { slots: Vec::new() }
+    }
+}
+
+impl<T> Components<T> {
+    pub fn insert(&mut self, entity: Entity, value: T) {
+        let idx = entity.index as usize;
+        if self.slots.len() <= idx {
+            self.slots.resize_with(idx + 1, || None);
+        }
+        self.slots[idx] = Some((entity.generation, value));
+    }
+
+    pub fn remove(&mut self, entity: Entity) -> Option<T> {
+        let slot = self.slots.get_mut(entity.index as usize)?;
+        match slot {
+            Some((generation, _)) if *generation == entity.generation => {
+                slot.take().map(|(_, value)| value)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&T> {
+        match self.slots.get(entity.index as usize) {
+            Some(Some((generation, value))) if *generation == entity.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, entity: Entity) -> Option<&mut T> {
+        match self.slots.get_mut(entity.index as usize) {
+            Some(Some((generation, value))) if *generation == entity.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.get(entity).is_some()
+    }
+
+    // in order of entity index, so iteration is deterministic
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &T)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.as_ref().map(|(generation, value)| {
+                (
+                    Entity {
+                        index: index as u32,
+                        generation: *generation,
+                    },
+                    value,
+                )
+            })
+        })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Entity, &mut T)> {
+        self.slots
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, slot)| {
+                slot.as_mut().map(|(generation, value)| {
+                    (
+                        Entity {
+                            index: index as u32,
+                            generation: *generation,
+                        },
+                        value,
+                    )
+                })
+            })
+    }
+}
+
+// how an entity is drawn
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Visual {
+    pub size: Vec2,
+    #[serde(with = "sheet_region_serde")]
+    pub uv: SheetRegion,
+}
+
+// entities touch when their centers are closer than the sum of their radii
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Collider {
+    pub radius: f32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct World<K> {
+    generations: Vec<u32>,
+    alive: Vec<bool>,
+    free: Vec<u32>,
+    doomed: Vec<Entity>,
+    pub kinds: Components<K>,
+    pub positions: Components<Vec2>,
+    pub velocities: Components<Vec2>,
+    pub visuals: Components<Visual>,
+    pub colliders: Components<Collider>,
+}
+
+impl<K> Default for World<K> {
+    fn default() -> Self {
+        World {
+            generations: Vec::new(),
+            alive: Vec::new(),
+            free: Vec::new(),
+            doomed: Vec::new(),
+            kinds: Components::default(),
+            positions: Components::default(),
+            velocities: Components::default(),
+            visuals: Components::default(),
+            colliders: Components::default(),
+        }
+    }
+}
+
+impl<K> World<K> {
+    pub fn spawn(&mut self, kind: K, pos: Vec2) -> Entity {
+        let entity = match self.free.pop() {
+            Some(index) => {
+                self.alive[index as usize] = true;
+                Entity {
+                    index,
+                    generation: self.generations[index as usize],
+                }
+            }
+            None => {
+                self.generations.push(0);
+                self.alive.push(true);
+                Entity {
+                    index: self.generations.len() as u32 - 1,
+                    generation: 0,
+                }
+            }
+        };
+        self.kinds.insert(entity, kind);
+        self.positions.insert(entity, pos);
+        entity
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        let idx = entity.index as usize;
+        idx < self.alive.len() && self.alive[idx] && self.generations[idx] == entity.generation
+    }
+
+    // the entity stays in the world until the next `flush`, so it's safe to
+    // call while iterating
+    pub fn despawn(&mut self, entity: Entity) {
+        if self.is_alive(entity) && !self.doomed.contains(&entity) {
+            self.doomed.push(entity);
+        }
+    }
+
+    // removes the entities passed to `despawn`
+    pub fn flush(&mut self) {
+        for entity in std::mem::take(&mut self.doomed) {
+            if !self.is_alive(entity) {
+                continue;
+            }
+            self.kinds.remove(entity);
+            self.positions.remove(entity);
+            self.velocities.remove(entity);
+            self.visuals.remove(entity);
+            self.colliders.remove(entity);
+            let idx = entity.index as usize;
+            self.alive[idx] = false;
+            self.generations[idx] += 1;
+            self.free.push(entity.index);
+        }
+    }
+
+    // removes every entity at once; like `flush`, it bumps the generations
+    // so ids held from before don't match the entities spawned after
+    pub fn clear(&mut self) {
+        for (idx, alive) in self.alive.iter_mut().enumerate() {
+            if *alive {
+                *alive = false;
+                self.generations[idx] += 1;
+            }
+        }
+        self.free = (0..self.alive.len() as u32).rev().collect();
+        self.doomed.clear();
+        self.kinds = Components::default();
+        self.positions = Components::default();
+        self.velocities = Components::default();
+        self.visuals = Components::default();
+        self.colliders = Components::default();
+    }
+
+    pub fn kind(&self, entity: Entity) -> Option<&K> {
+        self.kinds.get(entity)
+    }
+
+    pub fn pos(&self, entity: Entity) -> Option<Vec2> {
+        self.positions.get(entity).copied()
+    }
+
+    // entities whose kind matches `pred`
+    pub fn query<'a>(
+        &'a self,
+        pred: impl Fn(&K) -> bool + 'a,
+    ) -> impl Iterator<Item = (Entity, &'a K)> + 'a {
+        self.kinds.iter().filter(move |(_, kind)| pred(kind))
+    }
+
+    pub fn count(&self, pred: impl Fn(&K) -> bool) -> usize {
+        self.query(pred).count()
+    }
+
+    pub fn len(&self) -> usize {
+        self.kinds.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // movement system: adds each entity's velocity, scaled by `speed(kind)`
+    pub fn move_all(&mut self, speed: impl Fn(&K) -> f32) {
+        for (entity, vel) in self.velocities.iter() {
+            let scale = self.kinds.get(entity).map(&speed).unwrap_or(1.0);
+            if let Some(pos) = self.positions.get_mut(entity) {
+                *pos += *vel * scale;
+            }
+        }
+    }
+
+    // culling system: despawns entities whose position matches `pred`, e.g.
+    // everything that scrolled off the bottom of the screen
+    pub fn cull(&mut self, pred: impl Fn(Vec2) -> bool) {
+        let doomed: Vec<Entity> = self
+            .positions
+            .iter()
+            .filter(|(_, pos)| pred(**pos))
+            .map(|(entity, _)| entity)
+            .collect();
+        for entity in doomed {
+            self.despawn(entity);
+        }
+        self.flush();
+    }
+
+    // collision query: entities of a matching kind whose collider touches a
+    // circle of `radius` around `pos`, nearest first
+    pub fn touching(&self, pos: Vec2, radius: f32, pred: impl Fn(&K) -> bool) -> Vec<Entity> {
+        let mut hits: Vec<(Entity, f32)> = self
+            .colliders
+            .iter()
+            .filter(|(entity, _)| self.kinds.get(*entity).is_some_and(&pred))
+            .filter_map(|(entity, collider)| {
+                let dist = self.positions.get(entity)?.distance(pos);
+                (dist <= collider.radius + radius).then_some((entity, dist))
+            })
+            .collect();
+        hits.sort_by(|(_, d1), (_, d2)| d1.total_cmp(d2));
+        hits.into_iter().map(|(entity, _)| entity).collect()
+    }
+
+    pub fn sprite_count(&self) -> usize {
+        self.visuals
+            .iter()
+            .filter(|(entity, _)| self.positions.contains(*entity))
+            .count()
+    }
+
+    // rendering system: writes every entity with a visual into the sprite
+    // slices and returns how many were written
+    pub fn write_sprites(&self, transforms: &mut [Transform], uvs: &mut [SheetRegion]) -> usize {
+        let mut count = 0;
+        for (entity, visual) in self.visuals.iter() {
+            let Some(pos) = self.positions.get(entity) else {
+                continue;
+            };
+            if count >= transforms.len() || count >= uvs.len() {
+                break;
+            }
+            transforms[count] = geom::SPRITE {
+                center: *pos,
+                size: visual.size,
+            }
+            .into();
+            uvs[count] = visual.uv;
+            count += 1;
+        }
+        count
+    }
+}
+
+// frenderer's SheetRegion has no serde support, so store it as a tuple
+mod sheet_region_serde {
+    use frenderer::SheetRegion;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(uv: &SheetRegion, serializer: S) -> Result<S::Ok, S::Error> {
+        (uv.sheet, uv.x, uv.y, uv.depth, uv.w, uv.h).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<SheetRegion, D::Error> {
+        let (sheet, x, y, depth, w, h) = Deserialize::deserialize(deserializer)?;
+        Ok(SheetRegion::new(sheet, x, y, depth, w, h))
+    }
+}
@@ -0,0 +1,69 @@
+// A queue of typed gameplay events. Systems emit events (a coin was collected,
+// the player crashed, ...) instead of playing sounds or changing the score
+// themselves, and subscribers react to them when the queue is dispatched,
+// once per tick.
+//
+// Subscribers are plain functions over the game, usually its methods, e.g.
+// `events.subscribe(Game::play_sounds)`. The bus lives inside the game it
+// dispatches to, so `dispatch` takes a function to find it again.
+//
+// Delivery is deterministic: events go out in the order they were emitted,
+// each to every subscriber in the order they subscribed. Events emitted while
+// dispatching are delivered in the same dispatch, after the ones before them.
+
+// how many rounds of events emitted while dispatching are delivered before the
+// rest are left for the next tick, in case subscribers keep emitting
+const MAX_ROUNDS: usize = 16;
+
+pub type Subscriber<E, C> = fn(&mut C, &E);
+
+pub struct EventBus<E, C> {
+    queue: Vec<E>,
+    subscribers: Vec<Subscriber<E, C>>,
+}
+
+impl<E, C> Default for EventBus<E, C> {
+    fn default() -> Self {
+        EventBus {
+            queue: Vec::new(),
+            subscribers: Vec::new(),
+        }
+    }
+}
+
+impl<E, C> EventBus<E, C> {
+    pub fn subscribe(&mut self, subscriber: Subscriber<E, C>) {
+        self.subscribers.push(subscriber);
+    }
+
+    pub fn emit(&mut self, event: E) {
+        self.queue.push(event);
+    }
+
+    // events waiting for the next dispatch
+    pub fn pending(&self) -> &[E] {
+        &self.queue
+    }
+
+    // drops undelivered events, e.g. when loading a snapshot
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+
+    // delivers every pending event to every subscriber; `bus` finds this bus
+    // in `ctx`, e.g. `|game| &mut game.events`
+    pub fn dispatch(ctx: &mut C, bus: fn(&mut C) -> &mut Self) {
+        let subscribers = bus(ctx).subscribers.clone();
+        for _ in 0..MAX_ROUNDS {
+            let events = std::mem::take(&mut bus(ctx).queue);
+            if events.is_empty() {
+                break;
+            }
+            for event in events.iter() {
+                for subscriber in subscribers.iter() {
+                    subscriber(ctx, event);
+                }
+            }
+        }
+    }
+}
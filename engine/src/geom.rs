@@ -48,6 +48,15 @@ impl From<Rect> for Camera2D {
     }
 }
 
+impl From<Camera2D> for Rect {
+    fn from(val: Camera2D) -> Self {
+        Rect {
+            corner: val.screen_pos.into(),
+            size: val.screen_size.into(),
+        }
+    }
+}
+
 impl From<SPRITE> for Camera2D {
     fn from(val: SPRITE) -> Self {
         Camera2D {
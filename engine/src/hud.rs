@@ -0,0 +1,207 @@
+// Screen-anchored HUD elements. Elements are placed relative to an edge or
+// corner of the visible screen rectangle, so they stay in place when the
+// logical resolution or the camera's view changes.
+use crate::geom::{self, Rect};
+use crate::text::{Align, Font, TextStyle};
+use frenderer::{SheetRegion, Transform};
+use glam::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    // position within a box, from (0,0) at the bottom left to (1,1) at the top right
+    pub fn fraction(self) -> Vec2 {
+        let (x, y) = match self {
+            Anchor::TopLeft => (0.0, 1.0),
+            Anchor::Top => (0.5, 1.0),
+            Anchor::TopRight => (1.0, 1.0),
+            Anchor::Left => (0.0, 0.5),
+            Anchor::Center => (0.5, 0.5),
+            Anchor::Right => (1.0, 0.5),
+            Anchor::BottomLeft => (0.0, 0.0),
+            Anchor::Bottom => (0.5, 0.0),
+            Anchor::BottomRight => (1.0, 0.0),
+        };
+        Vec2 { x, y }
+    }
+
+    pub fn point(self, area: Rect) -> Vec2 {
+        area.corner + area.size * self.fraction()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum HudContent {
+    Sprite { size: Vec2, uv: SheetRegion },
+    // drawn with the HUD's font; the style's alignment lines up the lines within the block
+    Text { text: String, style: TextStyle },
+}
+
+#[derive(Clone, Debug)]
+pub struct HudElement {
+    // the point of the screen the element is attached to
+    pub anchor: Anchor,
+    // the point of the element that is placed at `anchor + offset`
+    pub pivot: Anchor,
+    pub offset: Vec2,
+    pub content: HudContent,
+    pub visible: bool,
+}
+
+impl HudElement {
+    pub fn new(anchor: Anchor, offset: Vec2, content: HudContent) -> Self {
+        HudElement {
+            anchor,
+            pivot: anchor,
+            offset,
+            content,
+            visible: true,
+        }
+    }
+
+    pub fn sprite(anchor: Anchor, offset: Vec2, size: Vec2, uv: SheetRegion) -> Self {
+        Self::new(anchor, offset, HudContent::Sprite { size, uv })
+    }
+
+    pub fn text(anchor: Anchor, offset: Vec2, text: &str, style: TextStyle) -> Self {
+        Self::new(
+            anchor,
+            offset,
+            HudContent::Text {
+                text: text.to_string(),
+                style,
+            },
+        )
+    }
+
+    pub fn pivot(self, pivot: Anchor) -> Self {
+        HudElement { pivot, ..self }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HudId(usize);
+
+pub struct Hud {
+    pub font: Font,
+    elements: Vec<HudElement>,
+}
+
+impl Hud {
+    pub fn new(font: Font) -> Self {
+        Hud {
+            font,
+            elements: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, element: HudElement) -> HudId {
+        self.elements.push(element);
+        HudId(self.elements.len() - 1)
+    }
+
+    pub fn element(&self, id: HudId) -> &HudElement {
+        &self.elements[id.0]
+    }
+
+    pub fn element_mut(&mut self, id: HudId) -> &mut HudElement {
+        &mut self.elements[id.0]
+    }
+
+    pub fn set_visible(&mut self, id: HudId, visible: bool) {
+        self.elements[id.0].visible = visible;
+    }
+
+    // replaces the text of a text element; does nothing for sprite elements
+    pub fn set_text(&mut self, id: HudId, new_text: &str) {
+        if let HudContent::Text { text, .. } = &mut self.elements[id.0].content {
+            if text != new_text {
+                text.clear();
+                text.push_str(new_text);
+            }
+        }
+    }
+
+    pub fn size(&self, id: HudId) -> Vec2 {
+        match &self.elements[id.0].content {
+            HudContent::Sprite { size, .. } => *size,
+            HudContent::Text { text, style } => self.font.measure(text, style),
+        }
+    }
+
+    // the area `id` occupies when the visible screen is `screen`
+    pub fn bounds(&self, id: HudId, screen: Rect) -> Rect {
+        let element = &self.elements[id.0];
+        let size = self.size(id);
+        Rect {
+            corner: element.anchor.point(screen) + element.offset - size * element.pivot.fraction(),
+            size,
+        }
+    }
+
+    pub fn sprite_count(&self) -> usize {
+        self.elements
+            .iter()
+            .filter(|element| element.visible)
+            .map(|element| match &element.content {
+                HudContent::Sprite { .. } => 1,
+                HudContent::Text { text, .. } => self.font.sprite_count(text),
+            })
+            .sum()
+    }
+
+    // lays out the visible elements for `screen` and writes them into the sprite slices;
+    // returns how many sprites were written
+    pub fn write_sprites(
+        &self,
+        screen: Rect,
+        transforms: &mut [Transform],
+        uvs: &mut [SheetRegion],
+    ) -> usize {
+        let limit = transforms.len().min(uvs.len());
+        let mut count = 0;
+        for (idx, element) in self.elements.iter().enumerate() {
+            if !element.visible || count == limit {
+                continue;
+            }
+            let bounds = self.bounds(HudId(idx), screen);
+            match &element.content {
+                HudContent::Sprite { uv, .. } => {
+                    transforms[count] = geom::SPRITE::from(bounds).into();
+                    uvs[count] = *uv;
+                    count += 1;
+                }
+                HudContent::Text { text, style } => {
+                    let line_x = match style.align {
+                        Align::Left => 0.0,
+                        Align::Center => 0.5,
+                        Align::Right => 1.0,
+                    };
+                    let top = Vec2 {
+                        x: bounds.corner.x + bounds.size.x * line_x,
+                        y: bounds.corner.y + bounds.size.y,
+                    };
+                    count += self.font.draw(
+                        &mut transforms[count..],
+                        &mut uvs[count..],
+                        text,
+                        top,
+                        style,
+                    );
+                }
+            }
+        }
+        count
+    }
+}
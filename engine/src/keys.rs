@@ -0,0 +1,69 @@
+// Keys pressed since the last frame, for things checked once a frame rather
+// than once a tick: the engine's hotkeys, and UI read from `Game::render`.
+// `Engine::input` is only cleared after a tick, so on a frame without one (a
+// fast display, or slow motion) a press read from it is seen again on the next
+// frame; these presses last exactly one frame instead. Held keys repeating
+// don't count as new presses.
+use frenderer::input::Key;
+use winit::event::{ElementState, Event, KeyboardInput, WindowEvent};
+
+#[derive(Clone, Debug, Default)]
+pub struct FrameKeys {
+    down: Vec<Key>,
+    pressed: Vec<Key>,
+}
+
+impl FrameKeys {
+    // call for every window event the game's keyboard should see
+    pub fn process_input_event<T>(&mut self, event: &Event<T>) {
+        match event {
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state,
+                                virtual_keycode: Some(key),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => match state {
+                ElementState::Pressed => self.press(*key),
+                ElementState::Released => self.release(*key),
+            },
+            // keys let go while the window is unfocused never report it
+            Event::WindowEvent {
+                event: WindowEvent::Focused(false),
+                ..
+            } => self.down.clear(),
+            _ => (),
+        }
+    }
+
+    pub fn press(&mut self, key: Key) {
+        if !self.down.contains(&key) {
+            self.down.push(key);
+            self.pressed.push(key);
+        }
+    }
+
+    pub fn release(&mut self, key: Key) {
+        self.down.retain(|k| *k != key);
+    }
+
+    pub fn is_key_down(&self, key: Key) -> bool {
+        self.down.contains(&key)
+    }
+
+    // true on the one frame after `key` went down
+    pub fn is_key_pressed(&self, key: Key) -> bool {
+        self.pressed.contains(&key)
+    }
+
+    // the engine calls this at the end of every frame
+    pub fn next_frame(&mut self) {
+        self.pressed.clear();
+    }
+}
@@ -5,17 +5,64 @@ pub use frenderer::{
     input::{Input, Key},
     wgpu, BitFont, Camera2D as Camera, Frenderer, SheetRegion, Transform,
 };
+// length of one simulation tick; `Game::update` runs this often
+pub const DT: f32 = 1.0 / 60.0;
+// fastest the time scale goes, so a frame never owes too many ticks
+pub const MAX_TIME_SCALE: f32 = 4.0;
+
 pub trait Game: Sized + 'static {
     fn new(engine: &mut Engine) -> Self;
     fn update(&mut self, engine: &mut Engine, acc: f32);
     fn is_game_over(&self) -> bool;
     fn render(&mut self, engine: &mut Engine);
+    // called when the game pauses or resumes; updates stop while paused but
+    // rendering goes on
+    fn paused(&mut self, _engine: &mut Engine, _paused: bool) {}
     // fn play_sound(&self, engine: &mut Engine, sound_data: &[u8]);
 }
 
 pub struct Engine {
     pub renderer: Frenderer,
     pub input: Input,
+    // presses since the last frame, for hotkeys and anything read in `render`
+    pub keys: keys::FrameKeys,
+    // window size and DPI scale, for converting window positions; see `coords`
+    pub viewport: coords::Viewport,
+    // mouse and touch presses, taps and swipes
+    pub pointer: pointer::Pointer,
+    // toggles pause; None to leave pausing to the game
+    pub pause_key: Option<Key>,
+    // pause while the window is unfocused or minimized
+    pub auto_pause: bool,
+    // toggle freezing time, and run a single tick while frozen
+    pub freeze_key: Option<Key>,
+    pub step_key: Option<Key>,
+    // toggles `show_debug`, for games to show a `debug::DebugOverlay`
+    pub debug_key: Option<Key>,
+    pub show_debug: bool,
+    // frame and scope timings; see `profiler`
+    pub profiler: profiler::Profiler,
+    // writes the profiler's history to `trace_path` as a Chrome trace
+    pub trace_key: Option<Key>,
+    pub trace_path: std::path::PathBuf,
+    // cvars and commands for tuning; see `console`
+    pub console: console::Console,
+    // opens and closes the console; while it is open the keyboard goes to it
+    pub console_key: Option<Key>,
+    // textures and data files, reloaded when they change with the `dev` feature
+    pub assets: assets::Assets,
+    time_scale: f32,
+    frozen: bool,
+    // ticks asked for with `step`
+    steps: u32,
+    user_paused: bool,
+    unfocused: bool,
+    minimized: bool,
+    // real seconds the last frame took
+    frame_time: f32,
+    // ticks run over the last second, and the ticks counted toward the next
+    tick_rate: f32,
+    ticks_counted: u32,
     // pub audio_sink: Sink,
     event_loop: Option<winit::event_loop::EventLoop<()>>,
     window: winit::window::Window,
@@ -31,27 +78,102 @@ impl Engine {
         // let audio_device = rodio::default_output_device().unwrap();
         // let audio_sink = Sink::new(&audio_device);
         let input = Input::default();
+        let viewport = coords::Viewport::new(window.inner_size(), window.scale_factor());
         Self {
             renderer,
             input,
+            keys: keys::FrameKeys::default(),
+            viewport,
+            pointer: pointer::Pointer::default(),
+            pause_key: Some(Key::P),
+            auto_pause: true,
+            freeze_key: Some(Key::F7),
+            step_key: Some(Key::F8),
+            debug_key: Some(Key::F3),
+            show_debug: false,
+            profiler: profiler::Profiler::default(),
+            trace_key: Some(Key::F10),
+            trace_path: std::path::PathBuf::from("trace.json"),
+            console: console::Console::default(),
+            console_key: Some(Key::Grave),
+            // the games run from their own folders, next to the content folder
+            assets: assets::Assets::new("../content"),
+            time_scale: 1.0,
+            frozen: false,
+            steps: 0,
+            user_paused: false,
+            unfocused: false,
+            minimized: false,
+            frame_time: 0.0,
+            tick_rate: 0.0,
+            ticks_counted: 0,
             // audio_sink,
             window,
             event_loop: Some(event_loop),
         }
     }
+    pub fn is_paused(&self) -> bool {
+        self.user_paused || (self.auto_pause && (self.unfocused || self.minimized))
+    }
+
+    // pauses or resumes the game; a pause from losing focus still holds until
+    // the window gets it back
+    pub fn set_paused(&mut self, paused: bool) {
+        self.user_paused = paused;
+    }
+
+    // how fast simulated time passes: 1 is normal, 0.5 slow motion and 0 frozen
+    pub fn time_scale(&self) -> f32 {
+        if self.frozen {
+            0.0
+        } else {
+            self.time_scale
+        }
+    }
+
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.time_scale = time_scale.clamp(0.0, MAX_TIME_SCALE);
+    }
+
+    // stops time without losing the time scale it comes back to
+    pub fn set_frozen(&mut self, frozen: bool) {
+        self.frozen = frozen;
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    // runs one tick on the next frame, even while frozen
+    pub fn step(&mut self) {
+        self.steps += 1;
+    }
+
+    pub fn frame_time(&self) -> f32 {
+        self.frame_time
+    }
+
+    // simulation ticks per second, counted over the last second
+    pub fn tick_rate(&self) -> f32 {
+        self.tick_rate
+    }
+
     pub fn run<G: Game>(mut self) {
         let mut game = G::new(&mut self);
-        const DT: f32 = 1.0 / 60.0;
         const DT_FUDGE_AMOUNT: f32 = 0.0002;
         const DT_MAX: f32 = DT * 5.0;
         const TIME_SNAPS: [f32; 5] = [15.0, 30.0, 60.0, 120.0, 144.0];
         let mut acc = 0.0;
+        let mut was_paused = false;
+        // the console key's own character, which shouldn't be typed
+        let mut swallow_char = false;
         let mut now = std::time::Instant::now();
+        let mut rate_start = now;
         self.event_loop
             .take()
             .unwrap()
             .run(move |event, _, control_flow| {
-                use winit::event::{Event, WindowEvent};
+                use winit::event::{ElementState, Event, KeyboardInput, WindowEvent};
                 control_flow.set_poll();
                 match event {
                     Event::WindowEvent {
@@ -61,13 +183,15 @@ impl Engine {
                         *control_flow = winit::event_loop::ControlFlow::Exit;
                     }
                     Event::MainEventsCleared => {
+                        self.profiler.begin_frame();
+                        self.assets.poll(&self.renderer.gpu);
                         // end game if there is a collision
                         if G::is_game_over(&game) == true {
                             *control_flow = winit::event_loop::ControlFlow::Exit;
                         }
                         // compute elapsed time since last frame
                         let mut elapsed = now.elapsed().as_secs_f32();
-                        // println!("{elapsed}");
+                        self.frame_time = elapsed;
                         // snap time to nearby vsync framerate
                         TIME_SNAPS.iter().for_each(|s| {
                             if (elapsed - 1.0 / s).abs() < DT_FUDGE_AMOUNT {
@@ -79,31 +203,202 @@ impl Engine {
                             acc = 0.0;
                             elapsed = DT;
                         }
-                        acc += elapsed;
+                        acc += elapsed * self.time_scale();
                         now = std::time::Instant::now();
+                        if let Some(key) = self.pause_key {
+                            if self.keys.is_key_pressed(key) {
+                                self.user_paused = !self.user_paused;
+                            }
+                        }
+                        if let Some(key) = self.freeze_key {
+                            if self.keys.is_key_pressed(key) {
+                                self.frozen = !self.frozen;
+                            }
+                        }
+                        if let Some(key) = self.step_key {
+                            if self.keys.is_key_pressed(key) && self.time_scale() == 0.0 {
+                                self.steps += 1;
+                            }
+                        }
+                        if let Some(key) = self.debug_key {
+                            if self.keys.is_key_pressed(key) {
+                                self.show_debug = !self.show_debug;
+                            }
+                        }
+                        if let Some(key) = self.trace_key {
+                            if self.keys.is_key_pressed(key) {
+                                match self.profiler.save_chrome_trace(&self.trace_path) {
+                                    Ok(()) => log::info!("wrote trace to {}", self.trace_path.display()),
+                                    Err(err) => log::error!("could not write trace: {err}"),
+                                }
+                            }
+                        }
+                        let paused = self.is_paused();
+                        if paused != was_paused {
+                            was_paused = paused;
+                            game.paused(&mut self, paused);
+                        }
+                        if paused {
+                            // drop the time spent paused and keep input current
+                            acc = 0.0;
+                            self.steps = 0;
+                        }
+                        // single steps run on top of any time owed
+                        while self.steps > 0 {
+                            self.steps -= 1;
+                            self.profiler.begin("update");
+                            game.update(&mut self, acc);
+                            self.profiler.end();
+                            self.profiler.tick();
+                            self.ticks_counted += 1;
+                            self.input.next_frame();
+                            self.pointer.next_frame();
+                        }
+                        if paused || self.time_scale() == 0.0 {
+                            self.input.next_frame();
+                            self.pointer.next_frame();
+                        }
                         // While we have time to spend
                         while acc >= DT {
                             // simulate a frame
                             acc -= DT;
+                            self.profiler.begin("update");
                             game.update(&mut self, acc);
+                            self.profiler.end();
+                            self.profiler.tick();
+                            self.ticks_counted += 1;
                             self.input.next_frame();
+                            self.pointer.next_frame();
+                        }
+                        let rate_elapsed = rate_start.elapsed().as_secs_f32();
+                        if rate_elapsed >= 1.0 {
+                            self.tick_rate = self.ticks_counted as f32 / rate_elapsed;
+                            self.ticks_counted = 0;
+                            rate_start = std::time::Instant::now();
                         }
+                        self.profiler.begin("render");
                         game.render(&mut self);
+                        self.profiler.end();
                         // Render prep
                         //self.renderer.sprites.set_camera_all(&frend.gpu, camera);
                         // update sprite positions and sheet regions
+                        self.profiler.begin("present");
                         self.renderer.render();
+                        self.profiler.end();
+                        self.profiler.end_frame();
+                        self.keys.next_frame();
                         self.window.request_redraw();
                     }
                     event => {
+                        let mut typed = false;
+                        match &event {
+                            Event::WindowEvent {
+                                event: WindowEvent::Resized(size),
+                                ..
+                            } => {
+                                self.viewport.resize(*size);
+                                self.minimized = size.width == 0 || size.height == 0;
+                            }
+                            Event::WindowEvent {
+                                event: WindowEvent::Focused(focused),
+                                ..
+                            } => {
+                                self.unfocused = !focused;
+                                // keys let go while unfocused never report it,
+                                // so forget every held key rather than leave
+                                // the game steering after it resumes
+                                if !focused {
+                                    self.input = Input::default();
+                                }
+                            }
+                            Event::WindowEvent {
+                                event: WindowEvent::Occluded(occluded),
+                                ..
+                            } => self.minimized = *occluded,
+                            Event::WindowEvent {
+                                event:
+                                    WindowEvent::ScaleFactorChanged {
+                                        scale_factor,
+                                        new_inner_size,
+                                    },
+                                ..
+                            } => self.viewport.rescale(*scale_factor, **new_inner_size),
+                            Event::WindowEvent {
+                                event:
+                                    WindowEvent::KeyboardInput {
+                                        input:
+                                            KeyboardInput {
+                                                state: ElementState::Pressed,
+                                                virtual_keycode: Some(key),
+                                                ..
+                                            },
+                                        ..
+                                    },
+                                ..
+                            } => {
+                                swallow_char = false;
+                                if Some(*key) == self.console_key {
+                                    self.console.toggle();
+                                    swallow_char = true;
+                                    typed = true;
+                                } else if self.console.is_open() {
+                                    self.console.key(*key);
+                                    typed = true;
+                                }
+                            }
+                            Event::WindowEvent {
+                                event: WindowEvent::ReceivedCharacter(c),
+                                ..
+                            } => {
+                                let swallowed = std::mem::take(&mut swallow_char);
+                                if !swallowed && self.console.is_open() {
+                                    self.console.type_char(*c);
+                                }
+                            }
+                            _ => (),
+                        }
                         if self.renderer.process_window_event(&event) {
                             self.window.request_redraw();
                         }
-                        self.input.process_input_event(&event);
+                        // key presses for the console don't reach the game
+                        if !typed {
+                            self.input.process_input_event(&event);
+                            self.keys.process_input_event(&event);
+                        }
+                        self.pointer.process_input_event(&event, &self.viewport);
                     }
                 }
             });
     }
 }
+pub mod assets;
+pub mod audio;
+pub mod camera;
+pub mod config;
+pub mod console;
+pub mod coords;
+pub mod debug;
+pub mod devtext;
+pub mod difficulty;
+pub mod ease;
+pub mod ecs;
+pub mod events;
 pub mod geom;
+pub mod hud;
+pub mod keys;
+pub mod logging;
+pub mod particles;
+pub mod pointer;
+pub mod profiler;
+pub mod rng;
+pub mod save;
+pub mod scheduler;
+pub mod snapshot;
+pub mod spawner;
 pub mod sprites;
+pub mod text;
+pub mod tiled;
+pub mod tilemap;
+pub mod transition;
+pub mod tween;
+pub mod ui;
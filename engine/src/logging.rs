@@ -0,0 +1,171 @@
+// Leveled, categorized logging through the `log` facade: use `log::info!`,
+// `log::debug!` and friends, with a `target:` naming the category where the
+// module path isn't a good one (e.g. `log::debug!(target: "player", "jump")`).
+//
+// `init` sends log lines to stderr through env_logger, filtered by RUST_LOG
+// (e.g. `RUST_LOG=debug` or `RUST_LOG=info,player=debug`), and keeps the most
+// recent ones for showing in game with a `LogView` (see `devtext::DevText`).
+use crate::camera;
+use crate::text::{Font, TextStyle};
+use frenderer::{Camera2D, SheetRegion, Transform};
+use glam::*;
+use log::{Level, Log, Metadata, Record};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+// log lines kept for `recent`
+const RECENT_MAX: usize = 64;
+// gap between a log view and the screen's edge
+const MARGIN: f32 = 8.0;
+
+static RECENT: Mutex<VecDeque<LogLine>> = Mutex::new(VecDeque::new());
+
+#[derive(Clone, Debug)]
+pub struct LogLine {
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+struct Logger {
+    inner: env_logger::Logger,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.inner.matches(record) {
+            return;
+        }
+        self.inner.log(record);
+        if let Ok(mut recent) = RECENT.lock() {
+            recent.push_back(LogLine {
+                level: record.level(),
+                target: record.target().to_string(),
+                message: record.args().to_string(),
+            });
+            while recent.len() > RECENT_MAX {
+                recent.pop_front();
+            }
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+// Starts logging; call once at the top of `main`. `default_filter` is used
+// when RUST_LOG isn't set, in the same syntax, e.g. "info".
+pub fn init(default_filter: &str) {
+    let inner = env_logger::Builder::from_env(
+        env_logger::Env::default().default_filter_or(default_filter),
+    )
+    .build();
+    let max_level = inner.filter();
+    match log::set_boxed_logger(Box::new(Logger { inner })) {
+        Ok(()) => log::set_max_level(max_level),
+        Err(err) => eprintln!("could not start logging: {err}"),
+    }
+}
+
+// the latest `count` log lines that passed the filter and are at least as
+// severe as `level`, oldest first
+pub fn recent(level: Level, count: usize) -> Vec<LogLine> {
+    let Ok(recent) = RECENT.lock() else {
+        return Vec::new();
+    };
+    let mut lines: Vec<LogLine> = recent
+        .iter()
+        .rev()
+        .filter(|line| line.level <= level)
+        .take(count)
+        .cloned()
+        .collect();
+    lines.reverse();
+    lines
+}
+
+// The most recent log lines drawn in the bottom left of the screen, newest at
+// the bottom. Call `refresh` once a frame before counting and writing its
+// sprites; it needs a font with letters, e.g. `UiSkin::ascii().font`.
+#[derive(Clone, Debug)]
+pub struct LogView {
+    pub visible: bool,
+    pub font: Font,
+    pub style: TextStyle,
+    // lines shown at most
+    pub lines: usize,
+    // lines less severe than this are left out
+    pub level: Level,
+    text: String,
+}
+
+impl LogView {
+    pub fn new(font: Font) -> Self {
+        LogView {
+            visible: false,
+            font,
+            style: TextStyle::sized(12.0),
+            lines: 8,
+            level: Level::Info,
+            text: String::new(),
+        }
+    }
+
+    pub fn style(self, style: TextStyle) -> Self {
+        LogView { style, ..self }
+    }
+
+    pub fn lines(self, lines: usize) -> Self {
+        LogView { lines, ..self }
+    }
+
+    pub fn level(self, level: Level) -> Self {
+        LogView { level, ..self }
+    }
+
+    // picks up the latest log lines
+    pub fn refresh(&mut self) {
+        if !self.visible {
+            self.text.clear();
+            return;
+        }
+        let shown: Vec<String> = recent(self.level, self.lines)
+            .iter()
+            .map(|line| format!("{} {}: {}", line.level, line.target, line.message))
+            .collect();
+        self.text = shown.join("\n");
+    }
+
+    pub fn sprite_count(&self) -> usize {
+        self.font.sprite_count(&self.text)
+    }
+
+    // writes the lines on a `screen_size` screen seen through `camera`;
+    // returns how many sprites were written
+    pub fn write_sprites(
+        &self,
+        camera: Camera2D,
+        screen_size: Vec2,
+        transforms: &mut [Transform],
+        uvs: &mut [SheetRegion],
+    ) -> usize {
+        let height = self.font.measure(&self.text, &self.style).y;
+        let count = self.font.draw(
+            transforms,
+            uvs,
+            &self.text,
+            Vec2 {
+                x: MARGIN,
+                y: MARGIN + height,
+            },
+            &self.style,
+        );
+        camera::screen_to_world(camera, screen_size, &mut transforms[..count]);
+        count
+    }
+}
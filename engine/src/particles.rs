@@ -0,0 +1,231 @@
+// Short-lived sprites for effects like sparks, dust and smoke. Each emitter
+// sends particles out continuously at `rate` per second, in bursts, or both.
+// Particles fly at a random speed and angle, fall under gravity, change size
+// over their life and step through `frames`; the sprite shader has no tint,
+// so a change of color is a change of frame.
+//
+// Effects draw their randomness from the system's own generator, seeded by the
+// game, so they play the same on every replay without disturbing the gameplay
+// generator. They are not part of snapshots.
+use crate::ease::Ease;
+use crate::geom;
+use crate::rng::GameRng;
+use crate::DT;
+use frenderer::{SheetRegion, Transform};
+use glam::*;
+use rand::Rng;
+use std::ops::Range;
+
+fn pick(rng: &mut impl Rng, range: &Range<f32>) -> f32 {
+    if range.start < range.end {
+        rng.gen_range(range.clone())
+    } else {
+        range.start
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Particle {
+    pos: Vec2,
+    vel: Vec2,
+    age: u32,
+    lifetime: u32,
+}
+
+impl Particle {
+    // 0 when spawned, 1 when it dies
+    fn life(&self) -> f32 {
+        self.age as f32 / self.lifetime as f32
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Emitter {
+    pub pos: Vec2,
+    // particles per second; 0 for bursts only
+    pub rate: f32,
+    // ticks each particle lives, chosen uniformly
+    pub lifetime: Range<u32>,
+    // units per second
+    pub speed: Range<f32>,
+    // direction in radians, counterclockwise from +x
+    pub angle: Range<f32>,
+    // units per second squared; negative y falls
+    pub gravity: Vec2,
+    // size at the start and end of a particle's life
+    pub size: (Vec2, Vec2),
+    pub size_ease: Ease,
+    // shown in order over a particle's life
+    pub frames: Vec<SheetRegion>,
+    // fraction of a particle owed by `rate`, carried to the next tick
+    carry: f32,
+    particles: Vec<Particle>,
+}
+
+impl Emitter {
+    pub fn new(frames: Vec<SheetRegion>) -> Self {
+        Emitter {
+            pos: Vec2::ZERO,
+            rate: 0.0,
+            lifetime: 30..60,
+            speed: 60.0..120.0,
+            angle: 0.0..std::f32::consts::TAU,
+            gravity: Vec2::ZERO,
+            size: (Vec2::splat(8.0), Vec2::splat(8.0)),
+            size_ease: Ease::Linear,
+            frames,
+            carry: 0.0,
+            particles: Vec::new(),
+        }
+    }
+
+    pub fn rate(self, rate: f32) -> Self {
+        Emitter { rate, ..self }
+    }
+
+    pub fn lifetime(self, lifetime: Range<u32>) -> Self {
+        Emitter { lifetime, ..self }
+    }
+
+    pub fn speed(self, speed: Range<f32>) -> Self {
+        Emitter { speed, ..self }
+    }
+
+    pub fn angle(self, angle: Range<f32>) -> Self {
+        Emitter { angle, ..self }
+    }
+
+    pub fn gravity(self, gravity: Vec2) -> Self {
+        Emitter { gravity, ..self }
+    }
+
+    pub fn size(self, start: Vec2, end: Vec2, ease: Ease) -> Self {
+        Emitter {
+            size: (start, end),
+            size_ease: ease,
+            ..self
+        }
+    }
+
+    fn emit(&mut self, pos: Vec2, rng: &mut impl Rng) {
+        let speed = pick(rng, &self.speed);
+        let angle = pick(rng, &self.angle);
+        let lifetime = if self.lifetime.is_empty() {
+            self.lifetime.start
+        } else {
+            rng.gen_range(self.lifetime.clone())
+        };
+        self.particles.push(Particle {
+            pos,
+            vel: Vec2::from_angle(angle) * speed,
+            age: 0,
+            lifetime: lifetime.max(1),
+        });
+    }
+
+    // advances every particle one tick, then emits for `rate`
+    fn update(&mut self, rng: &mut impl Rng) {
+        for particle in self.particles.iter_mut() {
+            particle.vel += self.gravity * DT;
+            particle.pos += particle.vel * DT;
+            particle.age += 1;
+        }
+        self.particles.retain(|p| p.age < p.lifetime);
+        self.carry += self.rate * DT;
+        while self.carry >= 1.0 {
+            self.carry -= 1.0;
+            self.emit(self.pos, rng);
+        }
+    }
+
+    pub fn particle_count(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+        self.carry = 0.0;
+    }
+
+    fn write_sprites(&self, transforms: &mut [Transform], uvs: &mut [SheetRegion]) -> usize {
+        if self.frames.is_empty() {
+            return 0;
+        }
+        let mut count = 0;
+        for (particle, (transform, uv)) in self
+            .particles
+            .iter()
+            .zip(transforms.iter_mut().zip(uvs.iter_mut()))
+        {
+            let life = particle.life();
+            *transform = geom::SPRITE {
+                center: particle.pos,
+                size: self.size.0.lerp(self.size.1, self.size_ease.apply(life)),
+            }
+            .into();
+            let frame = ((life * self.frames.len() as f32) as usize).min(self.frames.len() - 1);
+            *uv = self.frames[frame];
+            count += 1;
+        }
+        count
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Particles {
+    pub emitters: Vec<Emitter>,
+    rng: GameRng,
+}
+
+impl Particles {
+    // e.g. seeded from the game's `GameRng::seed` so effects replay with the run
+    pub fn new(seed: u64) -> Self {
+        Particles {
+            emitters: Vec::new(),
+            rng: GameRng::new(seed),
+        }
+    }
+
+    // returns the emitter's index, for bursts and moving it later
+    pub fn add(&mut self, emitter: Emitter) -> usize {
+        self.emitters.push(emitter);
+        self.emitters.len() - 1
+    }
+
+    // sends `count` particles out of emitter `emitter` from `pos` at once
+    pub fn burst(&mut self, emitter: usize, pos: Vec2, count: usize) {
+        let emitter = &mut self.emitters[emitter];
+        for _ in 0..count {
+            emitter.emit(pos, &mut self.rng);
+        }
+    }
+
+    // call once per tick
+    pub fn update(&mut self) {
+        for emitter in self.emitters.iter_mut() {
+            emitter.update(&mut self.rng);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.emitters.iter_mut().for_each(Emitter::clear);
+    }
+
+    pub fn sprite_count(&self) -> usize {
+        self.emitters
+            .iter()
+            .filter(|e| !e.frames.is_empty())
+            .map(Emitter::particle_count)
+            .sum()
+    }
+
+    // writes every particle into the sprite slices, emitter by emitter;
+    // returns how many were written
+    pub fn write_sprites(&self, transforms: &mut [Transform], uvs: &mut [SheetRegion]) -> usize {
+        let mut count = 0;
+        for emitter in self.emitters.iter() {
+            count += emitter.write_sprites(&mut transforms[count..], &mut uvs[count..]);
+        }
+        count
+    }
+}
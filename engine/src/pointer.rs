@@ -0,0 +1,224 @@
+// Mouse and touch input, with taps and swipes recognized from either. Winit
+// reports positions in window pixels; the `_world` methods convert them
+// through a camera (see `coords`). Mouse buttons are also available through
+// `Engine::input`.
+//
+// A press that is released quickly without moving is a tap; one that travels
+// far enough quickly is a swipe. Gestures are reported for one tick, like
+// `Input::is_key_pressed`. Distances are in logical pixels so gestures feel
+// the same on high-DPI screens.
+use crate::coords::Viewport;
+use frenderer::Camera2D;
+use glam::*;
+use winit::dpi::PhysicalPosition;
+use winit::event::{ElementState, Event, MouseButton, TouchPhase, WindowEvent};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SwipeDir {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Gesture {
+    // where the press was let go, in window pixels
+    Tap(PhysicalPosition<f64>),
+    Swipe(SwipeDir),
+}
+
+// where a press comes from; touches have an id per finger
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Source {
+    Mouse,
+    Touch(u64),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Press {
+    pub source: Source,
+    pub start: PhysicalPosition<f64>,
+    pub pos: PhysicalPosition<f64>,
+    // ticks since the press began
+    pub held: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct Pointer {
+    // a tap moves less than this many logical pixels and lasts at most `tap_ticks`
+    pub tap_distance: f32,
+    pub tap_ticks: u32,
+    // a swipe moves at least this many logical pixels within `swipe_ticks`
+    pub swipe_distance: f32,
+    pub swipe_ticks: u32,
+    cursor: PhysicalPosition<f64>,
+    // mouse and touch presses that are down, oldest first
+    presses: Vec<Press>,
+    gestures: Vec<Gesture>,
+    scale_factor: f32,
+}
+
+impl Default for Pointer {
+    fn default() -> Self {
+        Pointer {
+            tap_distance: 12.0,
+            tap_ticks: 20,
+            swipe_distance: 48.0,
+            swipe_ticks: 30,
+            cursor: PhysicalPosition::new(0.0, 0.0),
+            presses: Vec::new(),
+            gestures: Vec::new(),
+            scale_factor: 1.0,
+        }
+    }
+}
+
+impl Pointer {
+    // call for every window event, after `viewport` has seen it
+    pub fn process_input_event<T>(&mut self, event: &Event<T>, viewport: &Viewport) {
+        self.scale_factor = viewport.scale_factor;
+        let Event::WindowEvent { event, .. } = event else {
+            return;
+        };
+        match *event {
+            WindowEvent::CursorMoved { position, .. } => {
+                self.cursor = position;
+                self.move_press(Source::Mouse, position);
+            }
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Left,
+                ..
+            } => match state {
+                ElementState::Pressed => self.begin(Source::Mouse, self.cursor),
+                ElementState::Released => self.end(Source::Mouse),
+            },
+            WindowEvent::Touch(touch) => {
+                let source = Source::Touch(touch.id);
+                match touch.phase {
+                    TouchPhase::Started => self.begin(source, touch.location),
+                    TouchPhase::Moved => self.move_press(source, touch.location),
+                    TouchPhase::Ended => {
+                        self.move_press(source, touch.location);
+                        self.end(source);
+                    }
+                    // the system took the touch over, e.g. for its own gesture
+                    TouchPhase::Cancelled => self.presses.retain(|p| p.source != source),
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn begin(&mut self, source: Source, pos: PhysicalPosition<f64>) {
+        self.presses.retain(|p| p.source != source);
+        self.presses.push(Press {
+            source,
+            start: pos,
+            pos,
+            held: 0,
+        });
+    }
+
+    fn move_press(&mut self, source: Source, pos: PhysicalPosition<f64>) {
+        if let Some(press) = self.presses.iter_mut().find(|p| p.source == source) {
+            press.pos = pos;
+        }
+    }
+
+    fn end(&mut self, source: Source) {
+        let Some(i) = self.presses.iter().position(|p| p.source == source) else {
+            return;
+        };
+        let press = self.presses.remove(i);
+        if let Some(gesture) = self.recognize(&press) {
+            self.gestures.push(gesture);
+        }
+    }
+
+    fn recognize(&self, press: &Press) -> Option<Gesture> {
+        // window y points down
+        let moved = Vec2 {
+            x: (press.pos.x - press.start.x) as f32,
+            y: (press.start.y - press.pos.y) as f32,
+        } / self.scale_factor;
+        let distance = moved.length();
+        if distance < self.tap_distance && press.held <= self.tap_ticks {
+            Some(Gesture::Tap(press.pos))
+        } else if distance >= self.swipe_distance && press.held <= self.swipe_ticks {
+            let dir = if moved.x.abs() > moved.y.abs() {
+                if moved.x > 0.0 {
+                    SwipeDir::Right
+                } else {
+                    SwipeDir::Left
+                }
+            } else if moved.y > 0.0 {
+                SwipeDir::Up
+            } else {
+                SwipeDir::Down
+            };
+            Some(Gesture::Swipe(dir))
+        } else {
+            None
+        }
+    }
+
+    // call once per tick, after the game has read this tick's input
+    pub fn next_frame(&mut self) {
+        self.gestures.clear();
+        for press in self.presses.iter_mut() {
+            press.held += 1;
+        }
+    }
+
+    // the mouse cursor in window pixels
+    pub fn cursor(&self) -> PhysicalPosition<f64> {
+        self.cursor
+    }
+
+    pub fn cursor_world(&self, viewport: &Viewport, camera: Camera2D) -> Vec2 {
+        viewport.window_to_world(self.cursor, camera)
+    }
+
+    // mouse and touch presses that are down, oldest first
+    pub fn presses(&self) -> &[Press] {
+        &self.presses
+    }
+
+    // where each press is in the world, oldest first
+    pub fn presses_world(&self, viewport: &Viewport, camera: Camera2D) -> Vec<(Source, Vec2)> {
+        self.presses
+            .iter()
+            .map(|p| (p.source, viewport.window_to_world(p.pos, camera)))
+            .collect()
+    }
+
+    // where the oldest press is in the world, if anything is pressed
+    pub fn held_world(&self, viewport: &Viewport, camera: Camera2D) -> Option<Vec2> {
+        self.presses
+            .first()
+            .map(|p| viewport.window_to_world(p.pos, camera))
+    }
+
+    // gestures finished this tick
+    pub fn gestures(&self) -> &[Gesture] {
+        &self.gestures
+    }
+
+    // where a tap landed in the world this tick, if there was one
+    pub fn tapped_world(&self, viewport: &Viewport, camera: Camera2D) -> Option<Vec2> {
+        self.gestures.iter().find_map(|g| match g {
+            Gesture::Tap(pos) => Some(viewport.window_to_world(*pos, camera)),
+            _ => None,
+        })
+    }
+
+    pub fn is_tapped(&self) -> bool {
+        self.gestures.iter().any(|g| matches!(g, Gesture::Tap(_)))
+    }
+
+    pub fn swiped(&self, dir: SwipeDir) -> bool {
+        self.gestures.contains(&Gesture::Swipe(dir))
+    }
+}
@@ -0,0 +1,239 @@
+// Frame timings for finding out where the time goes. The engine times each
+// frame and the `update`, `render` and `present` scopes inside it; games time
+// their own systems by wrapping them in `begin`/`end` pairs, which nest.
+//
+// The last `history_len` frames are kept for looking at in game (averages,
+// the slowest frame, ticks per frame) and can be written out as a Chrome trace
+// (chrome://tracing or https://ui.perfetto.dev) for a closer look offline.
+use serde::Serialize;
+use std::{
+    collections::VecDeque,
+    io,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+#[derive(Clone, Debug)]
+pub struct ScopeTiming {
+    pub name: &'static str,
+    // since the start of the frame
+    pub start: Duration,
+    pub duration: Duration,
+    // how many scopes it is nested in
+    pub depth: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct FrameTiming {
+    // since the profiler was created
+    pub start: Duration,
+    pub duration: Duration,
+    // simulation ticks run in the frame
+    pub ticks: u32,
+    // in the order they ended
+    pub scopes: Vec<ScopeTiming>,
+}
+
+impl FrameTiming {
+    // total time spent in scopes called `name` during the frame
+    pub fn total(&self, name: &str) -> Duration {
+        self.scopes
+            .iter()
+            .filter(|scope| scope.name == name)
+            .map(|scope| scope.duration)
+            .sum()
+    }
+}
+
+// one "complete" event in Chrome's trace format; times are in microseconds
+#[derive(Serialize)]
+struct TraceEvent {
+    name: &'static str,
+    ph: &'static str,
+    ts: f64,
+    dur: f64,
+    pid: u32,
+    tid: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    args: Option<TraceArgs>,
+}
+
+#[derive(Serialize)]
+struct TraceArgs {
+    ticks: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Trace {
+    trace_events: Vec<TraceEvent>,
+    display_time_unit: &'static str,
+}
+
+#[derive(Clone, Debug)]
+pub struct Profiler {
+    // nothing is timed while this is false
+    pub enabled: bool,
+    // frames kept in the history
+    pub history_len: usize,
+    created: Instant,
+    // the frame being timed, and when it started
+    frame: Option<(Instant, FrameTiming)>,
+    // scopes begun but not yet ended
+    open: Vec<(&'static str, Instant)>,
+    history: VecDeque<FrameTiming>,
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Profiler {
+            enabled: true,
+            history_len: 300,
+            created: Instant::now(),
+            frame: None,
+            open: Vec::new(),
+            history: VecDeque::new(),
+        }
+    }
+}
+
+impl Profiler {
+    pub fn history_len(self, history_len: usize) -> Self {
+        Profiler {
+            history_len,
+            ..self
+        }
+    }
+
+    // starts timing a frame; the engine calls this
+    pub fn begin_frame(&mut self) {
+        if !self.enabled {
+            return;
+        }
+        let now = Instant::now();
+        self.open.clear();
+        self.frame = Some((
+            now,
+            FrameTiming {
+                start: now - self.created,
+                duration: Duration::ZERO,
+                ticks: 0,
+                scopes: Vec::new(),
+            },
+        ));
+    }
+
+    // finishes the frame and adds it to the history; scopes left open are
+    // dropped
+    pub fn end_frame(&mut self) {
+        let Some((start, mut frame)) = self.frame.take() else {
+            return;
+        };
+        self.open.clear();
+        frame.duration = start.elapsed();
+        self.history.push_back(frame);
+        while self.history.len() > self.history_len {
+            self.history.pop_front();
+        }
+    }
+
+    // counts a simulation tick toward the frame
+    pub fn tick(&mut self) {
+        if let Some((_, frame)) = &mut self.frame {
+            frame.ticks += 1;
+        }
+    }
+
+    pub fn begin(&mut self, name: &'static str) {
+        if self.frame.is_some() {
+            self.open.push((name, Instant::now()));
+        }
+    }
+
+    // ends the scope begun last
+    pub fn end(&mut self) {
+        let Some((frame_start, frame)) = &mut self.frame else {
+            return;
+        };
+        let Some((name, start)) = self.open.pop() else {
+            return;
+        };
+        frame.scopes.push(ScopeTiming {
+            name,
+            start: start - *frame_start,
+            duration: start.elapsed(),
+            depth: self.open.len(),
+        });
+    }
+
+    // oldest first
+    pub fn history(&self) -> impl Iterator<Item = &FrameTiming> {
+        self.history.iter()
+    }
+
+    pub fn last_frame(&self) -> Option<&FrameTiming> {
+        self.history.back()
+    }
+
+    pub fn slowest_frame(&self) -> Option<&FrameTiming> {
+        self.history.iter().max_by_key(|frame| frame.duration)
+    }
+
+    // mean time per frame spent in scopes called `name`, over the history
+    pub fn average(&self, name: &str) -> Duration {
+        if self.history.is_empty() {
+            return Duration::ZERO;
+        }
+        self.history.iter().map(|frame| frame.total(name)).sum::<Duration>()
+            / self.history.len() as u32
+    }
+
+    pub fn average_frame(&self) -> Duration {
+        if self.history.is_empty() {
+            return Duration::ZERO;
+        }
+        self.history.iter().map(|frame| frame.duration).sum::<Duration>()
+            / self.history.len() as u32
+    }
+
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+
+    // the history in Chrome's trace event format; each frame is an event
+    // holding its scopes, with its tick count as an argument
+    pub fn chrome_trace(&self) -> String {
+        let micros = |d: Duration| d.as_secs_f64() * 1_000_000.0;
+        let mut trace_events = Vec::new();
+        for frame in self.history.iter() {
+            trace_events.push(TraceEvent {
+                name: "frame",
+                ph: "X",
+                ts: micros(frame.start),
+                dur: micros(frame.duration),
+                pid: 1,
+                tid: 1,
+                args: Some(TraceArgs { ticks: frame.ticks }),
+            });
+            trace_events.extend(frame.scopes.iter().map(|scope| TraceEvent {
+                name: scope.name,
+                ph: "X",
+                ts: micros(frame.start + scope.start),
+                dur: micros(scope.duration),
+                pid: 1,
+                tid: 1,
+                args: None,
+            }));
+        }
+        let trace = Trace {
+            trace_events,
+            display_time_unit: "ms",
+        };
+        serde_json::to_string(&trace).unwrap_or_default()
+    }
+
+    pub fn save_chrome_trace(&self, path: &Path) -> io::Result<()> {
+        // a trace is only looked at once, so it is written without a backup
+        std::fs::write(path, self.chrome_trace())
+    }
+}
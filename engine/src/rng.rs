@@ -0,0 +1,45 @@
+use rand::{RngCore, SeedableRng};
+use rand_pcg::Pcg32;
+use serde::{Deserialize, Serialize};
+
+// A seeded random number generator for gameplay. Unlike `rand::thread_rng()` a
+// run can be replayed from its seed, and the state can be saved and restored.
+// Use it through the `rand::Rng` trait, e.g. `rng.gen_range(30..90)`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GameRng {
+    seed: u64,
+    rng: Pcg32,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        GameRng {
+            seed,
+            rng: Pcg32::seed_from_u64(seed),
+        }
+    }
+
+    // seeded from the operating system's entropy source
+    pub fn from_entropy() -> Self {
+        Self::new(rand::random())
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
@@ -0,0 +1,181 @@
+// Save data kept as JSON files, by default in the platform's data directory
+// (e.g. ~/.local/share/<game> on Linux). Set ENGINE_DATA_DIR to use another folder.
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const MAX_HIGH_SCORES: usize = 10;
+
+pub fn data_dir(game: &str) -> PathBuf {
+    if let Some(dir) = std::env::var_os("ENGINE_DATA_DIR") {
+        return PathBuf::from(dir).join(game);
+    }
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("save"))
+        .join(game)
+}
+
+// Writes to a temporary file and renames it over `path`, so a crash mid-write
+// never leaves a half-written file; the previous contents are kept as `<path>.bak`.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = with_suffix(path, "tmp");
+    {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+    }
+    if path.exists() {
+        fs::copy(path, with_suffix(path, "bak"))?;
+    }
+    fs::rename(&tmp, path)
+}
+
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    let json = serde_json::to_vec_pretty(value)?;
+    write_atomic(path, &json)
+}
+
+// Returns None if there is no save yet. A file that can't be read or parsed is
+// moved aside to `<path>.corrupt` and the backup from the last write is used instead.
+pub fn load_json<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    match read_json(path) {
+        Ok(value) => return Some(value),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => {
+            log::warn!("{}: {err}, falling back to backup", path.display());
+            let _ = fs::rename(path, with_suffix(path, "corrupt"));
+        }
+    }
+    let backup = with_suffix(path, "bak");
+    match read_json(&backup) {
+        Ok(value) => Some(value),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => {
+            log::error!("{}: {err}", backup.display());
+            None
+        }
+    }
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> io::Result<T> {
+    let bytes = fs::read(path)?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+// name to record scores under when the game doesn't ask for one
+pub fn player_name() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "player".to_string())
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ScoreEntry {
+    pub name: String,
+    pub score: u32,
+    // seconds since the Unix epoch
+    pub date: u64,
+    // seed of the run's `GameRng`
+    pub seed: u64,
+}
+
+impl ScoreEntry {
+    pub fn new(name: &str, score: u32, seed: u64) -> Self {
+        ScoreEntry {
+            name: name.to_string(),
+            score,
+            date: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            seed,
+        }
+    }
+
+    // the UTC day the score was set, as YYYY-MM-DD
+    pub fn date_string(&self) -> String {
+        // days to a civil date, from Howard Hinnant's `civil_from_days`
+        let days = (self.date / 86400) as i64 + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days.rem_euclid(146097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        format!("{year:04}-{month:02}-{day:02}")
+    }
+}
+
+// best scores first, at most MAX_HIGH_SCORES of them
+pub struct HighScores {
+    path: PathBuf,
+    entries: Vec<ScoreEntry>,
+}
+
+impl HighScores {
+    pub fn load(game: &str) -> Self {
+        Self::load_from(data_dir(game).join("high_scores.json"))
+    }
+
+    pub fn load_from(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let mut entries: Vec<ScoreEntry> = load_json(&path).unwrap_or_default();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.score));
+        entries.truncate(MAX_HIGH_SCORES);
+        HighScores { path, entries }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn entries(&self) -> &[ScoreEntry] {
+        &self.entries
+    }
+
+    pub fn top(&self, count: usize) -> &[ScoreEntry] {
+        &self.entries[..count.min(self.entries.len())]
+    }
+
+    pub fn qualifies(&self, score: u32) -> bool {
+        self.entries.len() < MAX_HIGH_SCORES
+            || self.entries.last().is_some_and(|last| score > last.score)
+    }
+
+    // adds the entry if it makes the table and returns its rank (0 is the best);
+    // earlier entries win ties
+    pub fn submit(&mut self, entry: ScoreEntry) -> Option<usize> {
+        if !self.qualifies(entry.score) {
+            return None;
+        }
+        let rank = self
+            .entries
+            .iter()
+            .position(|e| entry.score > e.score)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(rank, entry);
+        self.entries.truncate(MAX_HIGH_SCORES);
+        Some(rank)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        save_json(&self.path, &self.entries)
+    }
+}
@@ -0,0 +1,146 @@
+// Delayed and repeating events in tick units, for cooldowns and timed game
+// logic. Timers hold a game-defined event value (usually an enum) that `tick`
+// hands back when they fire, so the game handles them with full access to its
+// own state instead of through stored callbacks. Schedulers are serde types,
+// so pending timers are kept in a snapshot.
+//
+// Timers count down by `time_scale` each tick, so 0.5 runs them at half speed.
+// Pausing the scheduler stops every timer; pausing a timer stops just that one.
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub struct TimerId(u64);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Timer<E> {
+    id: TimerId,
+    event: E,
+    // ticks until it fires
+    remaining: f32,
+    // ticks between firings for a repeating timer
+    period: Option<f32>,
+    paused: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Scheduler<E> {
+    pub time_scale: f32,
+    paused: bool,
+    next_id: u64,
+    // in the order they were scheduled
+    timers: Vec<Timer<E>>,
+}
+
+impl<E> Default for Scheduler<E> {
+    fn default() -> Self {
+        Scheduler {
+            time_scale: 1.0,
+            paused: false,
+            next_id: 0,
+            timers: Vec::new(),
+        }
+    }
+}
+
+impl<E: Clone> Scheduler<E> {
+    fn schedule(&mut self, ticks: u32, period: Option<u32>, event: E) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+        self.timers.push(Timer {
+            id,
+            event,
+            remaining: ticks as f32,
+            period: period.map(|p| p.max(1) as f32),
+            paused: false,
+        });
+        id
+    }
+
+    // fires `event` once, `ticks` ticks from now
+    pub fn after(&mut self, ticks: u32, event: E) -> TimerId {
+        self.schedule(ticks, None, event)
+    }
+
+    // fires `event` every `ticks` ticks, starting `ticks` from now
+    pub fn every(&mut self, ticks: u32, event: E) -> TimerId {
+        self.schedule(ticks, Some(ticks), event)
+    }
+
+    // returns false if the timer had already fired or been cancelled
+    pub fn cancel(&mut self, id: TimerId) -> bool {
+        let len = self.timers.len();
+        self.timers.retain(|t| t.id != id);
+        self.timers.len() < len
+    }
+
+    pub fn clear(&mut self) {
+        self.timers.clear();
+    }
+
+    pub fn is_pending(&self, id: TimerId) -> bool {
+        self.timers.iter().any(|t| t.id == id)
+    }
+
+    // whole ticks until the timer next fires
+    pub fn remaining(&self, id: TimerId) -> Option<u32> {
+        self.timers
+            .iter()
+            .find(|t| t.id == id)
+            .map(|t| t.remaining.max(0.0).ceil() as u32)
+    }
+
+    pub fn len(&self) -> usize {
+        self.timers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    pub fn pause(&mut self, id: TimerId) {
+        self.timers
+            .iter_mut()
+            .filter(|t| t.id == id)
+            .for_each(|t| t.paused = true);
+    }
+
+    pub fn resume(&mut self, id: TimerId) {
+        self.timers
+            .iter_mut()
+            .filter(|t| t.id == id)
+            .for_each(|t| t.paused = false);
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    // Advances every running timer one tick and returns the events that fired,
+    // earliest first and in scheduling order on a tie. A repeating timer fires
+    // more than once in a tick if the time scale outpaces its period.
+    pub fn tick(&mut self) -> Vec<E> {
+        if self.paused {
+            return Vec::new();
+        }
+        let mut fired: Vec<(f32, TimerId, E)> = Vec::new();
+        for timer in self.timers.iter_mut().filter(|t| !t.paused) {
+            timer.remaining -= self.time_scale;
+            while timer.remaining <= 0.0 {
+                fired.push((timer.remaining, timer.id, timer.event.clone()));
+                match timer.period {
+                    Some(period) => timer.remaining += period,
+                    None => break,
+                }
+            }
+        }
+        self.timers
+            .retain(|t| t.period.is_some() || t.remaining > 0.0 || t.paused);
+        // the most overdue fired first
+        fired.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1 .0.cmp(&b.1 .0)));
+        fired.into_iter().map(|(_, _, event)| event).collect()
+    }
+}
@@ -0,0 +1,112 @@
+// Saving and restoring a game in progress. A game describes everything that
+// changes during play (positions, timers, score, RNG state...) as a serde type,
+// and these functions write it to or read it from a JSON file.
+use crate::{save, Engine};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{fmt, path::Path};
+
+pub trait Snapshot {
+    type State: Serialize + DeserializeOwned;
+    // stored in the file so one game's snapshot isn't loaded into another
+    const NAME: &'static str;
+    // bump when `State` changes shape so old snapshots are rejected
+    const VERSION: u32 = 1;
+
+    fn snapshot(&self) -> Self::State;
+    fn restore(&mut self, engine: &mut Engine, state: Self::State);
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnapshotFile<S> {
+    game: String,
+    version: u32,
+    state: S,
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    WrongGame(String),
+    WrongVersion(u32),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(err) => write!(f, "could not access snapshot: {err}"),
+            SnapshotError::Json(err) => write!(f, "invalid snapshot: {err}"),
+            SnapshotError::WrongGame(game) => write!(f, "snapshot is from another game ({game})"),
+            SnapshotError::WrongVersion(version) => {
+                write!(f, "snapshot is from an incompatible version ({version})")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(err: std::io::Error) -> Self {
+        SnapshotError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(err: serde_json::Error) -> Self {
+        SnapshotError::Json(err)
+    }
+}
+
+// the snapshot as JSON text, e.g. for attaching to a bug report
+pub fn to_json<G: Snapshot>(game: &G) -> Result<String, SnapshotError> {
+    Ok(serde_json::to_string_pretty(&SnapshotFile {
+        game: G::NAME.to_string(),
+        version: G::VERSION,
+        state: game.snapshot(),
+    })?)
+}
+
+// reads a snapshot's state, checking it was saved by this game and version;
+// the state is only read once those match, since another game or version may
+// store it in a different shape
+pub fn parse<G: Snapshot>(json: &str) -> Result<G::State, SnapshotError> {
+    let file: SnapshotFile<serde_json::Value> = serde_json::from_str(json)?;
+    if file.game != G::NAME {
+        return Err(SnapshotError::WrongGame(file.game));
+    }
+    if file.version != G::VERSION {
+        return Err(SnapshotError::WrongVersion(file.version));
+    }
+    Ok(serde_json::from_value(file.state)?)
+}
+
+pub fn from_json<G: Snapshot>(
+    game: &mut G,
+    engine: &mut Engine,
+    json: &str,
+) -> Result<(), SnapshotError> {
+    let state = parse::<G>(json)?;
+    game.restore(engine, state);
+    Ok(())
+}
+
+pub fn save<G: Snapshot>(game: &G, path: &Path) -> Result<(), SnapshotError> {
+    let json = to_json(game)?;
+    save::write_atomic(path, json.as_bytes())?;
+    Ok(())
+}
+
+pub fn load<G: Snapshot>(
+    game: &mut G,
+    engine: &mut Engine,
+    path: &Path,
+) -> Result<(), SnapshotError> {
+    let json = std::fs::read_to_string(path)?;
+    from_json(game, engine, &json)
+}
+
+// where a game's quick-save snapshot lives
+pub fn default_path<G: Snapshot>() -> std::path::PathBuf {
+    save::data_dir(G::NAME).join("snapshot.json")
+}
@@ -0,0 +1,154 @@
+// Spawns scrolling obstacles and pickups from a table of rules. Each rule picks
+// a kind by weight, a position in one of its lanes or within its x range, and
+// waits a random number of ticks before the next spawn. Rules are plain data
+// (and serde types), so they can be tuned or loaded from a file, and their
+// timers are saved along with the rest of a snapshot.
+use crate::ecs::{Entity, World};
+use glam::*;
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+// how many positions are tried before a spawn is put off to the next tick
+const MAX_ATTEMPTS: usize = 8;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Placement {
+    // one of these x positions
+    Lanes(Vec<f32>),
+    // anywhere in this x range
+    Range(f32, f32),
+}
+
+impl Placement {
+    fn pick_x(&self, rng: &mut impl Rng) -> Option<f32> {
+        match self {
+            Placement::Lanes(lanes) if lanes.is_empty() => None,
+            Placement::Lanes(lanes) => Some(lanes[rng.gen_range(0..lanes.len())]),
+            Placement::Range(min, max) if min < max => Some(rng.gen_range(*min..*max)),
+            Placement::Range(min, _) => Some(*min),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpawnRule<K> {
+    // kinds to choose from and their relative weights
+    pub kinds: Vec<(K, f32)>,
+    pub placement: Placement,
+    // height new entities appear at, usually just above the screen
+    pub y: f32,
+    // ticks between spawns, chosen uniformly
+    pub interval: Range<u32>,
+    // no spawn closer than this to any entity with a position
+    pub min_spacing: f32,
+    // at most this many of the rule's entities alive at once
+    pub max_live: usize,
+    // spawn rate multiplier, e.g. from a difficulty curve; 2.0 halves the interval
+    pub rate: f32,
+    timer: u32,
+    live: Vec<Entity>,
+}
+
+impl<K> SpawnRule<K> {
+    pub fn new(kinds: Vec<(K, f32)>, placement: Placement, y: f32) -> Self {
+        SpawnRule {
+            kinds,
+            placement,
+            y,
+            interval: 30..90,
+            min_spacing: 0.0,
+            max_live: usize::MAX,
+            rate: 1.0,
+            timer: 0,
+            live: Vec::new(),
+        }
+    }
+
+    pub fn interval(self, interval: Range<u32>) -> Self {
+        SpawnRule { interval, ..self }
+    }
+
+    pub fn min_spacing(self, min_spacing: f32) -> Self {
+        SpawnRule {
+            min_spacing,
+            ..self
+        }
+    }
+
+    pub fn max_live(self, max_live: usize) -> Self {
+        SpawnRule { max_live, ..self }
+    }
+
+    // how many entities from this rule are still in `world`
+    pub fn live_count<T>(&self, world: &World<T>) -> usize {
+        self.live.iter().filter(|e| world.is_alive(**e)).count()
+    }
+
+    fn next_interval(&self, rng: &mut impl Rng) -> u32 {
+        let ticks = if self.interval.is_empty() {
+            self.interval.start
+        } else {
+            rng.gen_range(self.interval.clone())
+        };
+        (ticks as f32 / self.rate.max(f32::EPSILON)).round() as u32
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Spawner<K> {
+    pub rules: Vec<SpawnRule<K>>,
+}
+
+impl<K> Default for Spawner<K> {
+    fn default() -> Self {
+        Spawner { rules: Vec::new() }
+    }
+}
+
+impl<K: Clone> Spawner<K> {
+    // returns the rule's index, for adjusting it later through `rules`
+    pub fn add(&mut self, rule: SpawnRule<K>) -> usize {
+        self.rules.push(rule);
+        self.rules.len() - 1
+    }
+
+    // Runs one tick of every rule, in order. `spawn` creates the entity for a
+    // chosen kind and position, adding whatever components it needs.
+    pub fn update(
+        &mut self,
+        world: &mut World<K>,
+        rng: &mut impl Rng,
+        mut spawn: impl FnMut(&mut World<K>, K, Vec2) -> Entity,
+    ) {
+        for rule in self.rules.iter_mut() {
+            rule.live.retain(|e| world.is_alive(*e));
+            if rule.timer > 0 {
+                rule.timer -= 1;
+                continue;
+            }
+            if rule.live.len() >= rule.max_live {
+                continue;
+            }
+            let Ok(weights) = WeightedIndex::new(rule.kinds.iter().map(|(_, w)| *w)) else {
+                continue;
+            };
+            // if every attempt lands too close to something, try again next tick
+            let pos = (0..MAX_ATTEMPTS)
+                .filter_map(|_| rule.placement.pick_x(rng))
+                .map(|x| Vec2 { x, y: rule.y })
+                .find(|pos| {
+                    world
+                        .positions
+                        .iter()
+                        .all(|(_, other)| pos.distance(*other) > rule.min_spacing)
+                });
+            let Some(pos) = pos else {
+                continue;
+            };
+            let kind = rule.kinds[weights.sample(rng)].0.clone();
+            rule.live.push(spawn(world, kind, pos));
+            rule.timer = rule.next_interval(rng);
+        }
+    }
+}
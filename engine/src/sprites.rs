@@ -1,9 +1,16 @@
+use crate::camera;
 use crate::geom;
+use crate::hud::Hud;
+use crate::save::ScoreEntry;
+use crate::text::{Align, Font, TextStyle};
+use crate::ui::Ui;
 
-use frenderer::{SheetRegion, BitFont, SpriteRenderer, WGPU, Camera2D};
+use bytemuck::Zeroable;
+use frenderer::{SheetRegion, SpriteRenderer, Transform, WGPU, Camera2D};
 use glam::*;
-use std::ops::RangeInclusive;
+use serde::{Deserialize, Serialize};
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Sprite {
     pub pos: Vec2,
     pub vel: Vec2,
@@ -111,34 +118,70 @@ pub fn handle_collisions(character: &mut Sprite, walls: &Vec<geom::SPRITE>, coll
     }
 }
 
-pub fn render_start_sprite(sprites: &mut SpriteRenderer, gpu: &mut WGPU, camera: Camera2D, width: f32, height: f32) {
-    let (transforms, uvs) = sprites.get_sprites_mut(1);
-                transforms[0] = geom::SPRITE {
-                    center: Vec2 {
-                        x: width / 2.0,
-                        y: height / 2.0,
-                    },
-                    size: Vec2 { x: width, y: height-(height/4.0) },
-                }
-                .into();
-                uvs[0] = SheetRegion::new(0, 0, 0, 0, 768, 864); // Adjust UV coordinates if needed
+// the title image within the start screen texture
+pub const START_SPRITE_UV: SheetRegion = SheetRegion::new(0, 0, 0, 0, 768, 864);
+
+// where the title image is drawn on a width x height screen
+pub fn start_sprite_area(width: f32, height: f32) -> geom::Rect {
+    geom::SPRITE {
+        center: Vec2 {
+            x: width / 2.0,
+            y: height / 2.0,
+        },
+        size: Vec2 { x: width, y: height-(height/4.0) },
+    }
+    .into()
+}
+
+pub fn render_start_sprite(sprites: &mut SpriteRenderer, gpu: &mut WGPU, camera: Camera2D, group: usize, width: f32, height: f32) {
+    let (transforms, uvs) = sprites.get_sprites_mut(group);
+                transforms[0] = start_sprite_area(width, height).into();
+                uvs[0] = START_SPRITE_UV; // Adjust UV coordinates if needed
 
                 sprites.resize_sprite_group(
                     gpu,
-                    1,
+                    group,
                     1,
                 );
                 sprites.upload_sprites(
                     gpu,
-                    1,
+                    group,
                     0..1,
                 );
                 sprites
                     .set_camera_all(gpu, camera);
 }
 
-pub fn render_end_sprite(font_end: &BitFont<RangeInclusive<char>>, sprites: &mut SpriteRenderer, gpu: &mut WGPU, score: u32, camera: Camera2D, font_y_offset: f32, width: f32, height: f32) {
-    let (transforms, uvs) = sprites.get_sprites_mut(0);
+// shrinks `group` to one blank sprite; groups are hidden like this rather
+// than removed so the indices of the groups after them stay put
+pub fn hide_sprite_group(sprites: &mut SpriteRenderer, gpu: &WGPU, group: usize) {
+    sprites.resize_sprite_group(gpu, group, 1);
+    let (transforms, uvs) = sprites.get_sprites_mut(group);
+    transforms[0] = Transform::zeroed();
+    uvs[0] = SheetRegion::zeroed();
+    sprites.upload_sprites(gpu, group, 0..1);
+}
+
+// where the end screen goes: its sprite group, the size of the screen, and
+// how far above the middle of the screen the score is drawn
+pub struct EndLayout {
+    pub group: usize,
+    pub font_y_offset: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+pub fn render_end_sprite(font_end: &Font, sprites: &mut SpriteRenderer, gpu: &mut WGPU, score: u32, camera: Camera2D, layout: EndLayout) {
+    let EndLayout { group, font_y_offset, width, height } = layout;
+    let score_str = score.to_string();
+    let end_text_len = font_end.sprite_count(&score_str);
+    sprites.resize_sprite_group(
+        gpu,
+        group,
+        1 + end_text_len,
+    );
+
+    let (transforms, uvs) = sprites.get_sprites_mut(group);
     transforms[0] = geom::SPRITE {
         center: Vec2 {
             x: width / 2.0,
@@ -149,61 +192,147 @@ pub fn render_end_sprite(font_end: &BitFont<RangeInclusive<char>>, sprites: &mut
     .into();
     uvs[0] = SheetRegion::new(0, 0, 0, 1, 768, 864); // Adjust UV coordinates if needed
 
-    let score_str = score.to_string();
-    let end_text_len = score_str.len();
-
-    font_end.draw_text(
-        sprites,
-        0,
-        1,
+    let end_text_len = font_end.draw(
+        &mut transforms[1..],
+        &mut uvs[1..],
         &score_str,
         Vec2 {
             x: (width / 2.0) + 60.0,
-            y: (height / 2.0) + font_y_offset as f32,
-        }
-        .into(),
-        40.0,
+            y: (height / 2.0) + font_y_offset,
+        },
+        &TextStyle::sized(40.0),
     );
 
-    sprites.resize_sprite_group(
-        &gpu,
-        0,
-        1 + end_text_len,
-    );
     sprites.upload_sprites(
-        &gpu,
-        0,
+        gpu,
+        group,
         0..1 + end_text_len,
     );
     sprites
-        .set_camera_all(&gpu, camera);
+        .set_camera_all(gpu, camera);
 }
 
-pub fn render_game_sprites(font: &BitFont<RangeInclusive<char>>, camera: Camera2D, sprites: &mut SpriteRenderer, sprite_count: usize, score_str: String, gpu: &mut WGPU, height: f32) {
-    let text_len = score_str.len();
-    font.draw_text(
-        sprites,
-        0,
-        sprite_count,
-        &score_str,
+// what was chosen on the pause menu
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PauseChoice {
+    Resume,
+    Quit,
+}
+
+// the pause menu, in the middle of a width x height screen. Call between
+// `Ui::begin` and `Ui::end`.
+pub fn pause_menu(ui: &mut Ui, width: f32, height: f32) -> Option<PauseChoice> {
+    let center = Vec2 {
+        x: width / 2.0,
+        y: height / 2.0,
+    };
+    ui.panel(geom::Rect {
+        corner: center - Vec2 { x: 180.0, y: 140.0 },
+        size: Vec2 { x: 360.0, y: 280.0 },
+    });
+    let style = ui.skin.text.align(Align::Center);
+    ui.label_styled("Paused", center + Vec2 { x: 0.0, y: 110.0 }, &style);
+    let button = |y: f32| geom::Rect {
+        corner: center + Vec2 { x: -120.0, y },
+        size: Vec2 { x: 240.0, y: 56.0 },
+    };
+    // both buttons are drawn every frame so focus keeps its place
+    let resume = ui.button(button(-4.0), "Resume");
+    let quit = ui.button(button(-100.0), "Quit");
+    if resume {
+        Some(PauseChoice::Resume)
+    } else if quit {
+        Some(PauseChoice::Quit)
+    } else {
+        None
+    }
+}
+
+// the game over summary: the high score table in the strip above the end
+// screen image, the run's score and a Quit button in the strip below it.
+// Returns true when Quit is chosen. Call between `Ui::begin` and `Ui::end`.
+pub fn end_summary(ui: &mut Ui, score: u32, high_scores: &[ScoreEntry], width: f32, height: f32) -> bool {
+    let strip = height / 8.0;
+    ui.panel(geom::Rect {
+        corner: Vec2 {
+            x: 0.0,
+            y: height - strip,
+        },
+        size: Vec2 { x: width, y: strip },
+    });
+    // one line per high score: rank, name, score, the day it was set and the run's seed
+    let table = high_scores
+        .iter()
+        .enumerate()
+        .map(|(rank, entry)| {
+            format!(
+                "{:>2} {:<12.12} {:>6} {} {:016x}",
+                rank + 1,
+                entry.name,
+                entry.score,
+                entry.date_string(),
+                entry.seed
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let style = TextStyle::sized(14.0);
+    ui.label_styled(
+        &table,
         Vec2 {
             x: 16.0,
-            y: height - 16.0,
-        }
-        .into(),
-        16.0,
+            y: height - 12.0,
+        },
+        &style,
     );
-    
+
+    ui.panel(geom::Rect {
+        corner: Vec2::ZERO,
+        size: Vec2 { x: width, y: strip },
+    });
+    ui.label(
+        &format!("Score {score}"),
+        Vec2 {
+            x: 32.0,
+            y: strip / 2.0 + 12.0,
+        },
+    );
+    ui.button(
+        geom::Rect {
+            corner: Vec2 {
+                x: width - 232.0,
+                y: strip / 2.0 - 28.0,
+            },
+            size: Vec2 { x: 200.0, y: 56.0 },
+        },
+        "Quit",
+    )
+}
+
+// `camera` shows the gameplay sprites; the HUD is laid out on a `screen_size`
+// screen and stays put however the camera moves or zooms
+pub fn render_game_sprites(hud: &Hud, camera: Camera2D, screen_size: Vec2, sprites: &mut SpriteRenderer, sprite_count: usize, gpu: &mut WGPU) {
     sprites.resize_sprite_group(
-        &gpu,
+        gpu,
         0,
-        sprite_count + text_len,
+        sprite_count + hud.sprite_count(),
+    );
+    let (transforms, uvs) = sprites.get_sprites_mut(0);
+    let hud_len = hud.write_sprites(
+        geom::Rect {
+            corner: Vec2::ZERO,
+            size: screen_size,
+        },
+        &mut transforms[sprite_count..],
+        &mut uvs[sprite_count..],
     );
+    camera::screen_to_world(camera, screen_size, &mut transforms[sprite_count..sprite_count + hud_len]);
+
     sprites.upload_sprites(
-        &gpu,
+        gpu,
         0,
-        0..sprite_count + text_len,
+        0..sprite_count + hud_len,
     );
     sprites
-        .set_camera_all(&gpu, camera);
+        .set_camera_all(gpu, camera);
 }
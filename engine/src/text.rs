@@ -0,0 +1,269 @@
+use crate::geom;
+use frenderer::{SheetRegion, Transform};
+use glam::*;
+use std::ops::RangeInclusive;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TextStyle {
+    // height (and width) of each character in world units
+    pub size: f32,
+    pub align: Align,
+    // distance between baselines as a multiple of `size`
+    pub line_spacing: f32,
+    // which copy of the glyphs to use, see `Font::with_tint`
+    pub tint: usize,
+    pub depth: u16,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        TextStyle {
+            size: 16.0,
+            align: Align::Left,
+            line_spacing: 1.25,
+            tint: 0,
+            depth: 0,
+        }
+    }
+}
+
+impl TextStyle {
+    pub fn sized(size: f32) -> Self {
+        TextStyle {
+            size,
+            ..Default::default()
+        }
+    }
+    pub fn align(self, align: Align) -> Self {
+        TextStyle { align, ..self }
+    }
+    pub fn tint(self, tint: usize) -> Self {
+        TextStyle { tint, ..self }
+    }
+    pub fn depth(self, depth: u16) -> Self {
+        TextStyle { depth, ..self }
+    }
+    fn line_height(&self) -> f32 {
+        self.size * self.line_spacing
+    }
+}
+
+// A monospaced bitmap font laid out like frenderer's `BitFont`: square glyphs
+// in `chars_per_row` columns starting at the top-left of `region`.
+// Unlike `BitFont` it handles newlines, alignment, wrapping and measuring, and
+// characters outside the font are drawn as '?' (or left blank) instead of panicking.
+#[derive(Clone, Debug)]
+pub struct Font {
+    chars: RangeInclusive<char>,
+    // one glyph grid per tint; the shader has no color input, so colors are
+    // separate copies of the glyphs elsewhere on the spritesheet
+    tints: Vec<SheetRegion>,
+    chars_per_row: u16,
+}
+
+impl Font {
+    pub fn with_sheet_region(
+        chars: RangeInclusive<char>,
+        uvs: SheetRegion,
+        chars_per_row: u16,
+    ) -> Self {
+        Font {
+            chars,
+            tints: vec![uvs],
+            chars_per_row,
+        }
+    }
+
+    // printable ASCII (' '..='~') laid out 16 glyphs per row
+    pub fn ascii(uvs: SheetRegion) -> Self {
+        Self::with_sheet_region(' '..='~', uvs, 16)
+    }
+
+    // adds a recolored copy of the glyph grid; its tint index is `tint_count() - 1`
+    pub fn with_tint(mut self, uvs: SheetRegion) -> Self {
+        self.tints.push(uvs);
+        self
+    }
+
+    pub fn tint_count(&self) -> usize {
+        self.tints.len()
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        self.chars.contains(&c)
+    }
+
+    pub fn glyph(&self, c: char, tint: usize) -> Option<SheetRegion> {
+        let c = if self.contains(c) {
+            c
+        } else if self.contains('?') {
+            '?'
+        } else {
+            return None;
+        };
+        let region = self.tints.get(tint).unwrap_or(&self.tints[0]);
+        let glyph_sz = region.w / self.chars_per_row;
+        let idx = u32::from(c) - u32::from(*self.chars.start());
+        let col = (idx % self.chars_per_row as u32) as u16;
+        let row = (idx / self.chars_per_row as u32) as u16;
+        Some(SheetRegion::new(
+            region.sheet,
+            region.x + col * glyph_sz,
+            region.y + row * glyph_sz,
+            region.depth,
+            glyph_sz,
+            glyph_sz,
+        ))
+    }
+
+    pub fn line_width(&self, line: &str, size: f32) -> f32 {
+        line.chars().count() as f32 * size
+    }
+
+    // size of the block of text when drawn with `style`
+    pub fn measure(&self, text: &str, style: &TextStyle) -> Vec2 {
+        let lines = text.lines().count().max(1);
+        let width = text
+            .lines()
+            .map(|line| self.line_width(line, style.size))
+            .fold(0.0, f32::max);
+        Vec2 {
+            x: width,
+            y: style.size + (lines - 1) as f32 * style.line_height(),
+        }
+    }
+
+    // number of sprites needed to draw `text` (whitespace is not drawn)
+    pub fn sprite_count(&self, text: &str) -> usize {
+        text.chars()
+            .filter(|c| !c.is_whitespace() && self.glyph(*c, 0).is_some())
+            .count()
+    }
+
+    // breaks `text` into lines no wider than `max_width`, splitting on spaces
+    // where possible and inside words that are too long on their own
+    pub fn wrap(&self, text: &str, max_width: f32, size: f32) -> Vec<String> {
+        let max_chars = ((max_width / size).floor() as usize).max(1);
+        let mut lines = Vec::new();
+        for paragraph in text.lines() {
+            let mut line = String::new();
+            for word in paragraph.split_whitespace() {
+                let mut word: Vec<char> = word.chars().collect();
+                let line_len = line.chars().count();
+                if line_len > 0 && line_len + 1 + word.len() <= max_chars {
+                    line.push(' ');
+                    line.extend(word);
+                    continue;
+                }
+                if line_len > 0 {
+                    lines.push(std::mem::take(&mut line));
+                }
+                while word.len() > max_chars {
+                    lines.push(word.drain(..max_chars).collect());
+                }
+                line.extend(word);
+            }
+            lines.push(line);
+        }
+        lines
+    }
+
+    // Draws `text` into the sprite slices and returns how many sprites were used.
+    // `pos` is the top of the first line: its left end, center or right end
+    // depending on the alignment.
+    pub fn draw(
+        &self,
+        transforms: &mut [Transform],
+        uvs: &mut [SheetRegion],
+        text: &str,
+        pos: Vec2,
+        style: &TextStyle,
+    ) -> usize {
+        self.draw_lines(transforms, uvs, text.lines(), pos, style)
+    }
+
+    // Wraps `text` to fit the width of `area` and draws it aligned inside it;
+    // lines that would fall below the bottom of `area` are dropped.
+    pub fn draw_in_box(
+        &self,
+        transforms: &mut [Transform],
+        uvs: &mut [SheetRegion],
+        text: &str,
+        area: geom::Rect,
+        style: &TextStyle,
+    ) -> usize {
+        let lines = self.wrap(text, area.size.x, style.size);
+        let fit = if area.size.y < style.size {
+            0
+        } else {
+            1 + ((area.size.y - style.size) / style.line_height()) as usize
+        };
+        let x = match style.align {
+            Align::Left => area.corner.x,
+            Align::Center => area.corner.x + area.size.x / 2.0,
+            Align::Right => area.corner.x + area.size.x,
+        };
+        let top = Vec2 {
+            x,
+            y: area.corner.y + area.size.y,
+        };
+        self.draw_lines(
+            transforms,
+            uvs,
+            lines.iter().map(String::as_str).take(fit),
+            top,
+            style,
+        )
+    }
+
+    fn draw_lines<'a>(
+        &self,
+        transforms: &mut [Transform],
+        uvs: &mut [SheetRegion],
+        lines: impl Iterator<Item = &'a str>,
+        pos: Vec2,
+        style: &TextStyle,
+    ) -> usize {
+        let limit = transforms.len().min(uvs.len());
+        let mut count = 0;
+        for (line_idx, line) in lines.enumerate() {
+            let width = self.line_width(line, style.size);
+            let left = match style.align {
+                Align::Left => pos.x,
+                Align::Center => pos.x - width / 2.0,
+                Align::Right => pos.x - width,
+            };
+            let center_y = pos.y - line_idx as f32 * style.line_height() - style.size / 2.0;
+            for (i, c) in line.chars().enumerate() {
+                if c.is_whitespace() {
+                    continue;
+                }
+                let Some(uv) = self.glyph(c, style.tint) else {
+                    continue;
+                };
+                if count == limit {
+                    return count;
+                }
+                transforms[count] = geom::SPRITE {
+                    center: Vec2 {
+                        x: left + (i as f32 + 0.5) * style.size,
+                        y: center_y,
+                    },
+                    size: Vec2::splat(style.size),
+                }
+                .into();
+                uvs[count] = uv.depth(style.depth);
+                count += 1;
+            }
+        }
+        count
+    }
+}
@@ -0,0 +1,379 @@
+// Importer for maps saved by the Tiled editor in its JSON format (.tmj/.json).
+use crate::tilemap::{Properties, Property, TileLayer, Tilemap, Tileset};
+use frenderer::SheetRegion;
+use glam::*;
+use serde::Deserialize;
+use std::{fmt, path::Path};
+
+// Tiled stores flip/rotation flags in the top bits of each tile id
+const GID_FLAG_MASK: u32 = 0x1FFF_FFFF;
+
+// an object from an object layer, e.g. a named spawn point
+#[derive(Clone, PartialEq, Debug)]
+pub struct SpawnObject {
+    pub id: u32,
+    pub name: String,
+    // the object's class ("type" in older Tiled versions)
+    pub kind: String,
+    // name of the object layer it came from
+    pub layer: String,
+    // center of the object in world space; for points, the point itself
+    pub pos: Vec2,
+    pub size: Vec2,
+    pub properties: Properties,
+}
+
+pub struct TiledMap {
+    pub tilemap: Tilemap,
+    pub spawns: Vec<SpawnObject>,
+}
+
+impl TiledMap {
+    pub fn spawn(&self, name: &str) -> Option<&SpawnObject> {
+        self.spawns.iter().find(|spawn| spawn.name == name)
+    }
+
+    pub fn spawns_of_kind<'a>(&'a self, kind: &'a str) -> impl Iterator<Item = &'a SpawnObject> {
+        self.spawns.iter().filter(move |spawn| spawn.kind == kind)
+    }
+}
+
+#[derive(Debug)]
+pub enum TiledError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    // only the JSON map format is supported
+    UnsupportedFormat(String),
+    // tile data that is base64 encoded or compressed
+    UnsupportedEncoding(String),
+    // tilesets saved in separate .tsj/.tsx files
+    ExternalTileset(String),
+    BadLayerSize(String),
+}
+
+impl fmt::Display for TiledError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TiledError::Io(err) => write!(f, "could not read map: {err}"),
+            TiledError::Json(err) => write!(f, "invalid Tiled JSON: {err}"),
+            TiledError::UnsupportedFormat(path) => {
+                write!(f, "{path}: only Tiled JSON maps (.tmj/.json) are supported")
+            }
+            TiledError::UnsupportedEncoding(layer) => {
+                write!(f, "layer {layer}: tile data must use the CSV layer format")
+            }
+            TiledError::ExternalTileset(source) => {
+                write!(
+                    f,
+                    "tileset {source}: external tilesets must be embedded in the map"
+                )
+            }
+            TiledError::BadLayerSize(layer) => {
+                write!(f, "layer {layer}: size does not match its tiles or the map")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TiledError {}
+
+impl From<std::io::Error> for TiledError {
+    fn from(err: std::io::Error) -> Self {
+        TiledError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for TiledError {
+    fn from(err: serde_json::Error) -> Self {
+        TiledError::Json(err)
+    }
+}
+
+pub fn load(path: impl AsRef<Path>) -> Result<TiledMap, TiledError> {
+    let path = path.as_ref();
+    if path.extension().is_some_and(|ext| ext == "tmx") {
+        return Err(TiledError::UnsupportedFormat(path.display().to_string()));
+    }
+    let json = std::fs::read_to_string(path)?;
+    from_json(&json)
+}
+
+pub fn from_json(json: &str) -> Result<TiledMap, TiledError> {
+    let raw: RawMap = serde_json::from_str(json)?;
+    let map_height = raw.height as f32 * raw.tileheight as f32;
+
+    let tilesets = raw
+        .tilesets
+        .iter()
+        .map(convert_tileset)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut layers = Vec::new();
+    let mut spawns = Vec::new();
+    collect_layers(&raw.layers, true, map_height, &mut layers, &mut spawns)?;
+    // tiles are placed by the map's size, so every layer has to cover it exactly
+    if let Some(layer) = layers
+        .iter()
+        .find(|layer| layer.width != raw.width || layer.height != raw.height)
+    {
+        return Err(TiledError::BadLayerSize(layer.name.clone()));
+    }
+    // earlier layers are drawn underneath later ones, and larger depths are further away
+    let layer_count = layers.len() as u16;
+    for (i, layer) in layers.iter_mut().enumerate() {
+        layer.depth = layer_count - i as u16;
+    }
+
+    Ok(TiledMap {
+        tilemap: Tilemap {
+            width: raw.width,
+            height: raw.height,
+            tile_size: Vec2 {
+                x: raw.tilewidth as f32,
+                y: raw.tileheight as f32,
+            },
+            tilesets,
+            layers,
+            properties: convert_properties(&raw.properties),
+        },
+        spawns,
+    })
+}
+
+fn collect_layers(
+    raw_layers: &[RawLayer],
+    parent_visible: bool,
+    map_height: f32,
+    layers: &mut Vec<TileLayer>,
+    spawns: &mut Vec<SpawnObject>,
+) -> Result<(), TiledError> {
+    for raw in raw_layers {
+        let visible = parent_visible && raw.visible;
+        match raw.kind.as_str() {
+            "tilelayer" => {
+                let data = match &raw.data {
+                    Some(RawData::Csv(data)) => &data[..],
+                    Some(RawData::Encoded) => {
+                        return Err(TiledError::UnsupportedEncoding(raw.name.clone()))
+                    }
+                    None => &[],
+                };
+                if data.len() != raw.width * raw.height {
+                    return Err(TiledError::BadLayerSize(raw.name.clone()));
+                }
+                layers.push(TileLayer {
+                    name: raw.name.clone(),
+                    width: raw.width,
+                    height: raw.height,
+                    tiles: data.iter().map(|gid| gid & GID_FLAG_MASK).collect(),
+                    visible,
+                    depth: 0,
+                    properties: convert_properties(&raw.properties),
+                });
+            }
+            "objectgroup" => {
+                spawns.extend(
+                    raw.objects
+                        .iter()
+                        .map(|obj| convert_object(obj, &raw.name, map_height)),
+                );
+            }
+            "group" => collect_layers(&raw.layers, visible, map_height, layers, spawns)?,
+            // image layers have nothing to import
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn convert_tileset(raw: &RawTileset) -> Result<Tileset, TiledError> {
+    if let Some(source) = &raw.source {
+        return Err(TiledError::ExternalTileset(source.clone()));
+    }
+    Ok(Tileset {
+        name: raw.name.clone(),
+        image: raw.image.clone(),
+        region: SheetRegion::new(
+            0,
+            raw.margin,
+            raw.margin,
+            0,
+            raw.imagewidth.saturating_sub(2 * raw.margin),
+            raw.imageheight.saturating_sub(2 * raw.margin),
+        ),
+        tile_w: raw.tilewidth,
+        tile_h: raw.tileheight,
+        columns: raw.columns,
+        spacing: raw.spacing,
+        tile_count: raw.tilecount,
+        first_gid: raw.firstgid,
+    })
+}
+
+fn convert_object(raw: &RawObject, layer: &str, map_height: f32) -> SpawnObject {
+    let size = Vec2 {
+        x: raw.width,
+        y: raw.height,
+    };
+    // Tiled measures y downwards from the top of the map; rectangles are anchored
+    // at their top-left corner, except tile objects which use the bottom-left
+    let top = if raw.gid.is_some() {
+        raw.y - raw.height
+    } else {
+        raw.y
+    };
+    let pos = Vec2 {
+        x: raw.x + size.x / 2.0,
+        y: map_height - top - size.y / 2.0,
+    };
+    let kind = if raw.class.is_empty() {
+        raw.kind.clone()
+    } else {
+        raw.class.clone()
+    };
+    SpawnObject {
+        id: raw.id,
+        name: raw.name.clone(),
+        kind,
+        layer: layer.to_string(),
+        pos,
+        size,
+        properties: convert_properties(&raw.properties),
+    }
+}
+
+fn convert_properties(raw: &[RawProperty]) -> Properties {
+    raw.iter()
+        .map(|prop| {
+            let value = match (&prop.kind[..], &prop.value) {
+                ("bool", serde_json::Value::Bool(b)) => Property::Bool(*b),
+                ("int" | "object", value) => Property::Int(value.as_i64().unwrap_or_default()),
+                ("float", value) => Property::Float(value.as_f64().unwrap_or_default()),
+                // strings, colors and file paths
+                (_, serde_json::Value::String(s)) => Property::String(s.clone()),
+                (_, value) => Property::String(value.to_string()),
+            };
+            (prop.name.clone(), value)
+        })
+        .collect()
+}
+
+// the subset of Tiled's JSON map format that gets imported
+
+#[derive(Deserialize)]
+struct RawMap {
+    width: usize,
+    height: usize,
+    tilewidth: u32,
+    tileheight: u32,
+    #[serde(default)]
+    tilesets: Vec<RawTileset>,
+    #[serde(default)]
+    layers: Vec<RawLayer>,
+    #[serde(default)]
+    properties: Vec<RawProperty>,
+}
+
+#[derive(Deserialize)]
+struct RawTileset {
+    firstgid: u32,
+    source: Option<String>,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    imagewidth: u16,
+    #[serde(default)]
+    imageheight: u16,
+    #[serde(default)]
+    tilewidth: u16,
+    #[serde(default)]
+    tileheight: u16,
+    #[serde(default)]
+    columns: u16,
+    #[serde(default)]
+    tilecount: u32,
+    #[serde(default)]
+    margin: u16,
+    #[serde(default)]
+    spacing: u16,
+}
+
+#[derive(Deserialize)]
+struct RawLayer {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default = "default_visible")]
+    visible: bool,
+    #[serde(default)]
+    width: usize,
+    #[serde(default)]
+    height: usize,
+    data: Option<RawData>,
+    #[serde(default)]
+    objects: Vec<RawObject>,
+    #[serde(default)]
+    layers: Vec<RawLayer>,
+    #[serde(default)]
+    properties: Vec<RawProperty>,
+}
+
+// "csv" layers store a plain array, "base64" layers a (possibly compressed) string
+enum RawData {
+    Csv(Vec<u32>),
+    Encoded,
+}
+
+impl<'de> Deserialize<'de> for RawData {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Data {
+            Csv(Vec<u32>),
+            Encoded(serde::de::IgnoredAny),
+        }
+        Ok(match Data::deserialize(deserializer)? {
+            Data::Csv(data) => RawData::Csv(data),
+            Data::Encoded(_) => RawData::Encoded,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct RawObject {
+    id: u32,
+    #[serde(default)]
+    name: String,
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    class: String,
+    gid: Option<u32>,
+    x: f32,
+    y: f32,
+    #[serde(default)]
+    width: f32,
+    #[serde(default)]
+    height: f32,
+    #[serde(default)]
+    properties: Vec<RawProperty>,
+}
+
+#[derive(Deserialize)]
+struct RawProperty {
+    name: String,
+    #[serde(rename = "type", default = "default_property_type")]
+    kind: String,
+    value: serde_json::Value,
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+fn default_property_type() -> String {
+    "string".to_string()
+}
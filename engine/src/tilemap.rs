@@ -0,0 +1,157 @@
+use crate::geom;
+use frenderer::{SheetRegion, Transform};
+use glam::*;
+use std::collections::HashMap;
+
+// custom properties attached to maps, layers and objects (e.g. from Tiled)
+#[derive(Clone, PartialEq, Debug)]
+pub enum Property {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+pub type Properties = HashMap<String, Property>;
+
+// a grid of equally sized tiles cut out of a region of a spritesheet
+#[derive(Clone, Debug)]
+pub struct Tileset {
+    pub name: String,
+    // image the tiles come from, as written in the map file
+    pub image: String,
+    // where the tileset image lives in the spritesheet
+    pub region: SheetRegion,
+    pub tile_w: u16,
+    pub tile_h: u16,
+    pub columns: u16,
+    pub spacing: u16,
+    pub tile_count: u32,
+    // global id of this tileset's first tile
+    pub first_gid: u32,
+}
+
+impl Tileset {
+    pub fn contains(&self, gid: u32) -> bool {
+        gid >= self.first_gid && gid < self.first_gid + self.tile_count
+    }
+
+    pub fn sheet_region(&self, gid: u32) -> Option<SheetRegion> {
+        if !self.contains(gid) || self.columns == 0 {
+            return None;
+        }
+        let idx = gid - self.first_gid;
+        let col = (idx % self.columns as u32) as u16;
+        let row = (idx / self.columns as u32) as u16;
+        Some(SheetRegion::new(
+            self.region.sheet,
+            self.region.x + col * (self.tile_w + self.spacing),
+            self.region.y + row * (self.tile_h + self.spacing),
+            self.region.depth,
+            self.tile_w,
+            self.tile_h,
+        ))
+    }
+}
+
+// tiles are stored row by row starting from the top row, 0 means no tile
+#[derive(Clone, PartialEq, Debug)]
+pub struct TileLayer {
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    pub tiles: Vec<u32>,
+    pub visible: bool,
+    // sprite depth used for every tile of this layer
+    pub depth: u16,
+    pub properties: Properties,
+}
+
+impl TileLayer {
+    pub fn tile(&self, col: usize, row: usize) -> Option<u32> {
+        if col >= self.width || row >= self.height {
+            return None;
+        }
+        match self.tiles[row * self.width + col] {
+            0 => None,
+            gid => Some(gid),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Tilemap {
+    // size of the map in tiles
+    pub width: usize,
+    pub height: usize,
+    // size of one tile in world units
+    pub tile_size: Vec2,
+    pub tilesets: Vec<Tileset>,
+    pub layers: Vec<TileLayer>,
+    pub properties: Properties,
+}
+
+impl Tilemap {
+    pub fn size(&self) -> Vec2 {
+        Vec2 {
+            x: self.width as f32 * self.tile_size.x,
+            y: self.height as f32 * self.tile_size.y,
+        }
+    }
+
+    pub fn layer(&self, name: &str) -> Option<&TileLayer> {
+        self.layers.iter().find(|layer| layer.name == name)
+    }
+
+    pub fn sheet_region(&self, gid: u32) -> Option<SheetRegion> {
+        self.tilesets
+            .iter()
+            .find(|tileset| tileset.contains(gid))
+            .and_then(|tileset| tileset.sheet_region(gid))
+    }
+
+    // center of the tile at (col, row) in world space, with row 0 at the top of the map
+    pub fn tile_center(&self, col: usize, row: usize) -> Vec2 {
+        Vec2 {
+            x: (col as f32 + 0.5) * self.tile_size.x,
+            y: (self.height - row) as f32 * self.tile_size.y - self.tile_size.y / 2.0,
+        }
+    }
+
+    // number of sprites needed to draw every visible, non-empty tile
+    pub fn sprite_count(&self) -> usize {
+        self.layers
+            .iter()
+            .filter(|layer| layer.visible)
+            .map(|layer| layer.tiles.iter().filter(|&&gid| gid != 0).count())
+            .sum()
+    }
+
+    // writes the visible tiles into the given sprite slices and returns how many were written
+    pub fn write_sprites(&self, transforms: &mut [Transform], uvs: &mut [SheetRegion]) -> usize {
+        let mut count = 0;
+        for layer in self.layers.iter().filter(|layer| layer.visible) {
+            for row in 0..layer.height {
+                for col in 0..layer.width {
+                    let Some(gid) = layer.tile(col, row) else {
+                        continue;
+                    };
+                    let Some(uv) = self.sheet_region(gid) else {
+                        continue;
+                    };
+                    if count >= transforms.len() || count >= uvs.len() {
+                        return count;
+                    }
+                    transforms[count] = geom::SPRITE {
+                        center: self.tile_center(col, row),
+                        size: self.tile_size,
+                    }
+                    .into();
+                    uvs[count] = uv.depth(layer.depth);
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+}
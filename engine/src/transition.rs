@@ -0,0 +1,308 @@
+// Screen transitions between scenes, drawn as an overlay on top of the scene
+// in a sprite group of their own.
+// The sprite renderer has no blending, so fades are drawn as a dissolve: the
+// overlay is split into a grid of cells that are covered one by one in an
+// ordered dither pattern.
+//
+// A transition first covers the screen and then uncovers it; `tick` reports
+// the moment it is fully covered, which is when the game should switch scenes.
+// A crossfade instead starts fully covered by the outgoing scene's image and
+// dissolves it away, so the switch happens on the first tick.
+use crate::ease::Ease;
+use crate::geom::{self, Rect};
+use crate::sprites::hide_sprite_group;
+use bytemuck::Zeroable;
+use frenderer::{wgpu, Camera2D, Frenderer, SheetRegion, SpriteRenderer, Transform, WGPU};
+use glam::*;
+
+// 4x4 ordered dither thresholds
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+// the direction a wipe's edge travels
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WipeDir {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TransitionKind {
+    // dissolve to the cover and back
+    Fade,
+    // dissolve the cover, usually the outgoing scene's image, away
+    Crossfade,
+    // slide the cover across the screen and out the other side
+    Wipe(WipeDir),
+    // close a circle around the center of the screen and open it again
+    Iris,
+}
+
+// the sprite group a transition draws into, and what covers the screen: a
+// solid color (see `add_color_group`) or an image on the group's texture
+#[derive(Clone, Copy, Debug)]
+pub struct Overlay {
+    pub group: usize,
+    pub cover: SheetRegion,
+}
+
+#[derive(Clone, Debug)]
+pub struct Transition {
+    pub kind: TransitionKind,
+    pub overlay: Overlay,
+    // number of dither cells across and up the screen
+    pub cells: UVec2,
+    pub ease: Ease,
+    ticks: u32,
+    elapsed: u32,
+}
+
+impl Transition {
+    // `ticks` is the length of the whole transition
+    pub fn new(kind: TransitionKind, overlay: Overlay, ticks: u32) -> Self {
+        Transition {
+            kind,
+            overlay,
+            cells: UVec2 { x: 16, y: 24 },
+            ease: Ease::SmoothStep,
+            ticks: ticks.max(1),
+            elapsed: 0,
+        }
+    }
+
+    pub fn fade(overlay: Overlay, ticks: u32) -> Self {
+        Self::new(TransitionKind::Fade, overlay, ticks)
+    }
+
+    // `outgoing` is usually the outgoing scene's own group and image, which
+    // the crossfade dissolves in place
+    pub fn crossfade(outgoing: Overlay, ticks: u32) -> Self {
+        Self::new(TransitionKind::Crossfade, outgoing, ticks)
+    }
+
+    pub fn wipe(dir: WipeDir, overlay: Overlay, ticks: u32) -> Self {
+        Self::new(TransitionKind::Wipe(dir), overlay, ticks)
+    }
+
+    pub fn iris(overlay: Overlay, ticks: u32) -> Self {
+        Self::new(TransitionKind::Iris, overlay, ticks)
+    }
+
+    pub fn cells(self, x: u32, y: u32) -> Self {
+        Transition {
+            cells: UVec2 {
+                x: x.max(1),
+                y: y.max(1),
+            },
+            ..self
+        }
+    }
+
+    // 0 at the start, 1 when done
+    pub fn progress(&self) -> f32 {
+        self.elapsed as f32 / self.ticks as f32
+    }
+
+    // how much of the screen is covered, from 0 to 1
+    pub fn coverage(&self) -> f32 {
+        let t = self.progress();
+        match self.kind {
+            TransitionKind::Crossfade => self.ease.apply(1.0 - t),
+            _ if t < 0.5 => self.ease.apply(t * 2.0),
+            _ => self.ease.apply(2.0 - t * 2.0),
+        }
+    }
+
+    fn switch_tick(&self) -> u32 {
+        match self.kind {
+            TransitionKind::Crossfade => 1,
+            _ => (self.ticks / 2).max(1),
+        }
+    }
+
+    // advances one tick; returns true on the tick the scene should be switched
+    pub fn tick(&mut self) -> bool {
+        if self.is_done() {
+            return false;
+        }
+        self.elapsed += 1;
+        self.elapsed == self.switch_tick()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.elapsed >= self.ticks
+    }
+
+    // the covered parts of `area` and what to draw in each
+    fn pieces(&self, area: Rect) -> Vec<(Rect, SheetRegion)> {
+        let coverage = self.coverage();
+        if coverage <= 0.0 {
+            return Vec::new();
+        }
+        if let TransitionKind::Wipe(dir) = self.kind {
+            // the cover enters from one edge and leaves through the other
+            let (start, end) = if self.progress() < 0.5 {
+                (0.0, coverage)
+            } else {
+                (1.0 - coverage, 1.0)
+            };
+            let (corner, size) = match dir {
+                WipeDir::Right => (
+                    Vec2 { x: start, y: 0.0 },
+                    Vec2 {
+                        x: end - start,
+                        y: 1.0,
+                    },
+                ),
+                WipeDir::Left => (
+                    Vec2 {
+                        x: 1.0 - end,
+                        y: 0.0,
+                    },
+                    Vec2 {
+                        x: end - start,
+                        y: 1.0,
+                    },
+                ),
+                WipeDir::Up => (
+                    Vec2 { x: 0.0, y: start },
+                    Vec2 {
+                        x: 1.0,
+                        y: end - start,
+                    },
+                ),
+                WipeDir::Down => (
+                    Vec2 {
+                        x: 0.0,
+                        y: 1.0 - end,
+                    },
+                    Vec2 {
+                        x: 1.0,
+                        y: end - start,
+                    },
+                ),
+            };
+            let rect = Rect {
+                corner: area.corner + corner * area.size,
+                size: size * area.size,
+            };
+            return vec![(rect, self.overlay.cover)];
+        }
+        let cell_size = area.size / self.cells.as_vec2();
+        let center = area.corner + area.size / 2.0;
+        let iris_radius = (1.0 - coverage) * (area.size / 2.0).length();
+        let mut pieces = Vec::new();
+        for row in 0..self.cells.y {
+            for col in 0..self.cells.x {
+                let rect = Rect {
+                    corner: area.corner
+                        + Vec2 {
+                            x: col as f32,
+                            y: row as f32,
+                        } * cell_size,
+                    size: cell_size,
+                };
+                let covered = match self.kind {
+                    TransitionKind::Iris => {
+                        (rect.corner + cell_size / 2.0).distance(center) >= iris_radius
+                    }
+                    _ => {
+                        let threshold = BAYER[row as usize % 4][col as usize % 4];
+                        coverage > (threshold as f32 + 0.5) / 16.0
+                    }
+                };
+                if !covered {
+                    continue;
+                }
+                let uv = if self.kind == TransitionKind::Crossfade {
+                    self.cover_cell(col, row)
+                } else {
+                    self.overlay.cover
+                };
+                pieces.push((rect, uv));
+            }
+        }
+        pieces
+    }
+
+    // the part of the cover image shown in a cell; rows count up from the
+    // bottom of the screen but down from the top of the sheet
+    fn cover_cell(&self, col: u32, row: u32) -> SheetRegion {
+        let split = |start: u16, len: u16, i: u32, n: u32| {
+            let a = start as u32 + len as u32 * i / n;
+            let b = start as u32 + len as u32 * (i + 1) / n;
+            (a as u16, (b - a) as u16)
+        };
+        let cover = self.overlay.cover;
+        let (x, w) = split(cover.x, cover.w, col, self.cells.x);
+        let (y, h) = split(cover.y, cover.h, self.cells.y - 1 - row, self.cells.y);
+        SheetRegion::new(cover.sheet, x, y, cover.depth, w, h)
+    }
+
+    pub fn sprite_count(&self, area: Rect) -> usize {
+        self.pieces(area).len()
+    }
+
+    // writes the overlay for `area` into the sprite slices; returns how many
+    // sprites were written
+    pub fn write_sprites(
+        &self,
+        area: Rect,
+        transforms: &mut [Transform],
+        uvs: &mut [SheetRegion],
+    ) -> usize {
+        let mut count = 0;
+        for ((rect, uv), (transform, region)) in self
+            .pieces(area)
+            .into_iter()
+            .zip(transforms.iter_mut().zip(uvs.iter_mut()))
+        {
+            *transform = geom::SPRITE::from(rect).into();
+            *region = uv;
+            count += 1;
+        }
+        count
+    }
+
+    // resizes the overlay's group to fit, writes it and uploads it
+    pub fn render(&self, sprites: &mut SpriteRenderer, gpu: &WGPU, area: Rect) {
+        let group = self.overlay.group;
+        let count = self.sprite_count(area);
+        sprites.resize_sprite_group(gpu, group, count.max(1));
+        let (transforms, uvs) = sprites.get_sprites_mut(group);
+        if self.write_sprites(area, transforms, uvs) == 0 {
+            transforms[0] = Transform::zeroed();
+        }
+        sprites.upload_sprites(gpu, group, 0..count.max(1));
+    }
+
+    // empties the overlay's group once the transition is over; after a
+    // crossfade this hides the outgoing scene for good
+    pub fn clear(&self, sprites: &mut SpriteRenderer, gpu: &WGPU) {
+        hide_sprite_group(sprites, gpu, self.overlay.group);
+    }
+}
+
+// Adds a sprite group with a single-color texture for fades, wipes and irises.
+// Groups are drawn in order, so add it last to draw the overlay above
+// everything else.
+pub fn add_color_group(renderer: &mut Frenderer, color: [u8; 4], camera: Camera2D) -> Overlay {
+    let texture = renderer.gpu.create_texture(
+        &color,
+        wgpu::TextureFormat::Rgba8UnormSrgb,
+        (1, 1),
+        Some("transition-color"),
+    );
+    let group = renderer.sprites.add_sprite_group(
+        &renderer.gpu,
+        &texture,
+        vec![Transform::zeroed(); 1],
+        vec![SheetRegion::zeroed(); 1],
+        camera,
+    );
+    Overlay {
+        group,
+        cover: SheetRegion::new(0, 0, 0, 0, 1, 1),
+    }
+}
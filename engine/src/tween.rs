@@ -0,0 +1,160 @@
+// Tweens move a value from one point to another over a number of ticks with an
+// easing curve, e.g. a jump arc, a sprite sliding off screen or a pop-up
+// growing in. Advance them once per `Game::update`; they are serde types so
+// motion in progress is kept in a snapshot.
+pub use crate::ease::Ease;
+use glam::*;
+use serde::{Deserialize, Serialize};
+
+// values that can be tweened
+pub trait Lerp: Copy {
+    fn lerp(self, to: Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        crate::ease::lerp(self, to, t)
+    }
+}
+
+impl Lerp for Vec2 {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        Vec2::lerp(self, to, t)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Tween<T> {
+    pub from: T,
+    pub to: T,
+    pub ticks: u32,
+    pub ease: Ease,
+    elapsed: u32,
+}
+
+impl<T: Lerp> Tween<T> {
+    pub fn new(from: T, to: T, ticks: u32) -> Self {
+        Tween {
+            from,
+            to,
+            ticks,
+            ease: Ease::Linear,
+            elapsed: 0,
+        }
+    }
+
+    pub fn eased(self, ease: Ease) -> Self {
+        Tween { ease, ..self }
+    }
+
+    // 0 at the start, 1 when done
+    pub fn progress(&self) -> f32 {
+        if self.ticks == 0 {
+            1.0
+        } else {
+            self.elapsed as f32 / self.ticks as f32
+        }
+    }
+
+    pub fn value(&self) -> T {
+        self.from.lerp(self.to, self.ease.apply(self.progress()))
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.elapsed >= self.ticks
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = 0;
+    }
+
+    // advances one tick and returns the new value
+    pub fn tick(&mut self) -> T {
+        self.elapsed = (self.elapsed + 1).min(self.ticks);
+        self.value()
+    }
+
+    // like `tick`, calling `on_complete` with the final value on the tick the
+    // tween finishes
+    pub fn tick_then(&mut self, on_complete: impl FnOnce(T)) -> T {
+        let was_done = self.is_done();
+        let value = self.tick();
+        if !was_done && self.is_done() {
+            on_complete(value);
+        }
+        value
+    }
+}
+
+// Tweens played one after another, each starting where the last one ended.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Sequence<T> {
+    start: T,
+    steps: Vec<Tween<T>>,
+    current: usize,
+}
+
+impl<T: Lerp> Sequence<T> {
+    pub fn new(start: T) -> Self {
+        Sequence {
+            start,
+            steps: Vec::new(),
+            current: 0,
+        }
+    }
+
+    // adds a step from the end of the previous one to `to`
+    pub fn then(mut self, to: T, ticks: u32, ease: Ease) -> Self {
+        let from = self.steps.last().map_or(self.start, |step| step.to);
+        self.steps.push(Tween::new(from, to, ticks).eased(ease));
+        self
+    }
+
+    // holds the current end value for `ticks`
+    pub fn wait(self, ticks: u32) -> Self {
+        let at = self.steps.last().map_or(self.start, |step| step.to);
+        self.then(at, ticks, Ease::Linear)
+    }
+
+    // index of the step being played; equal to the step count when done
+    pub fn step(&self) -> usize {
+        self.current
+    }
+
+    pub fn value(&self) -> T {
+        match self.steps.get(self.current) {
+            Some(step) => step.value(),
+            None => self.steps.last().map_or(self.start, |step| step.to),
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.current >= self.steps.len()
+    }
+
+    pub fn reset(&mut self) {
+        self.steps.iter_mut().for_each(Tween::reset);
+        self.current = 0;
+    }
+
+    pub fn tick(&mut self) -> T {
+        if let Some(step) = self.steps.get_mut(self.current) {
+            step.tick();
+            if step.is_done() {
+                self.current += 1;
+            }
+        }
+        self.value()
+    }
+
+    // like `tick`, calling `on_complete` with the final value on the tick the
+    // last step finishes
+    pub fn tick_then(&mut self, on_complete: impl FnOnce(T)) -> T {
+        let was_done = self.is_done();
+        let value = self.tick();
+        if !was_done && self.is_done() {
+            on_complete(value);
+        }
+        value
+    }
+}
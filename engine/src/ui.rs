@@ -0,0 +1,269 @@
+// Immediate-mode widgets: call the widget functions every frame between
+// `Ui::begin` and `Ui::end`, and `end` writes the result into a sprite group.
+// Keys are read from `Engine::keys`, so a press moves focus once even on
+// frames without a tick.
+use crate::geom::Rect;
+use crate::keys::FrameKeys;
+use crate::text::{Align, Font, TextStyle};
+use bytemuck::Zeroable;
+use frenderer::{input::Key, SheetRegion, SpriteRenderer, Transform, WGPU};
+use glam::*;
+
+// focus movement and activation, from the keyboard or fed in by the game (e.g. from a gamepad)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UiNav {
+    Up,
+    Down,
+    Left,
+    Right,
+    Activate,
+}
+
+// where each widget's pieces come from on the spritesheet
+#[derive(Clone, Debug)]
+pub struct UiSkin {
+    pub font: Font,
+    pub text: TextStyle,
+    pub panel: SheetRegion,
+    pub button: SheetRegion,
+    pub button_focused: SheetRegion,
+    pub bar_back: SheetRegion,
+    pub bar_fill: SheetRegion,
+}
+
+impl UiSkin {
+    // the skin for content/ascii.png: the font's 16x6 grid of 8x8 glyphs, with
+    // the widget swatches in the row below it
+    pub fn ascii() -> Self {
+        let swatch = |i: u16| SheetRegion::new(0, i * 8 + 1, 49, 0, 6, 6);
+        UiSkin {
+            font: Font::ascii(SheetRegion::new(0, 0, 0, 0, 128, 48)),
+            text: TextStyle::sized(24.0),
+            panel: swatch(0),
+            button: swatch(1),
+            button_focused: swatch(2),
+            bar_back: swatch(3),
+            bar_fill: swatch(4),
+        }
+    }
+}
+
+pub struct Ui {
+    pub skin: UiSkin,
+    transforms: Vec<Transform>,
+    uvs: Vec<SheetRegion>,
+    // index of the focused widget among the focusable widgets of a frame
+    focus: usize,
+    focusable: usize,
+    last_focusable: usize,
+    nav: Vec<UiNav>,
+}
+
+// panels are drawn behind widgets, widgets behind text
+const PANEL_DEPTH: u16 = 3;
+const WIDGET_DEPTH: u16 = 2;
+const TEXT_DEPTH: u16 = 1;
+
+impl Ui {
+    pub fn new(skin: UiSkin) -> Self {
+        Ui {
+            skin,
+            transforms: Vec::with_capacity(64),
+            uvs: Vec::with_capacity(64),
+            focus: 0,
+            focusable: 0,
+            last_focusable: 0,
+            nav: Vec::new(),
+        }
+    }
+
+    // queue navigation from a source other than the keyboard; applies on the next `begin`
+    pub fn navigate(&mut self, nav: UiNav) {
+        self.nav.push(nav);
+    }
+
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+
+    pub fn set_focus(&mut self, focus: usize) {
+        self.focus = focus;
+    }
+
+    // starts a new frame of widgets, reading arrow keys/WASD and Enter/Space
+    // from `Engine::keys`
+    pub fn begin(&mut self, keys: &FrameKeys) {
+        let bindings = [
+            (Key::Up, UiNav::Up),
+            (Key::W, UiNav::Up),
+            (Key::Down, UiNav::Down),
+            (Key::S, UiNav::Down),
+            (Key::Left, UiNav::Left),
+            (Key::A, UiNav::Left),
+            (Key::Right, UiNav::Right),
+            (Key::D, UiNav::Right),
+            (Key::Return, UiNav::Activate),
+            (Key::Space, UiNav::Activate),
+        ];
+        for (key, nav) in bindings {
+            if keys.is_key_pressed(key) {
+                self.nav.push(nav);
+            }
+        }
+        self.transforms.clear();
+        self.uvs.clear();
+        self.last_focusable = self.focusable;
+        self.focusable = 0;
+        let count = self.last_focusable;
+        for nav in self.nav.iter() {
+            match nav {
+                UiNav::Up if count > 0 => self.focus = (self.focus + count - 1) % count,
+                UiNav::Down if count > 0 => self.focus = (self.focus + 1) % count,
+                _ => {}
+            }
+        }
+        if count > 0 {
+            self.focus = self.focus.min(count - 1);
+        }
+        self.nav
+            .retain(|nav| !matches!(nav, UiNav::Up | UiNav::Down));
+    }
+
+    pub fn panel(&mut self, area: Rect) {
+        self.push(area.into(), self.skin.panel.depth(PANEL_DEPTH));
+    }
+
+    // `pos` is interpreted as in `Font::draw`, according to the skin's alignment
+    pub fn label(&mut self, text: &str, pos: Vec2) {
+        let style = self.skin.text.depth(TEXT_DEPTH);
+        self.text(text, pos, &style);
+    }
+
+    pub fn label_styled(&mut self, text: &str, pos: Vec2, style: &TextStyle) {
+        self.text(text, pos, &style.depth(TEXT_DEPTH));
+    }
+
+    // returns true on the frame the button is activated
+    pub fn button(&mut self, area: Rect, text: &str) -> bool {
+        let focused = self.next_focusable();
+        let uv = if focused {
+            self.skin.button_focused
+        } else {
+            self.skin.button
+        };
+        self.push(area.into(), uv.depth(WIDGET_DEPTH));
+        self.centered_text(text, area);
+        focused && self.take_nav(UiNav::Activate)
+    }
+
+    // a focusable bar whose value moves by `step` with left/right; returns true if it changed
+    pub fn slider(&mut self, area: Rect, value: &mut f32, min: f32, max: f32, step: f32) -> bool {
+        let focused = self.next_focusable();
+        let old = *value;
+        if focused {
+            if self.take_nav(UiNav::Left) {
+                *value -= step;
+            }
+            if self.take_nav(UiNav::Right) {
+                *value += step;
+            }
+        }
+        *value = value.clamp(min, max);
+        let fraction = if max > min {
+            (*value - min) / (max - min)
+        } else {
+            0.0
+        };
+        self.bar(area, fraction, focused);
+        *value != old
+    }
+
+    // fraction is clamped to 0..=1
+    pub fn progress_bar(&mut self, area: Rect, fraction: f32) {
+        self.bar(area, fraction, false);
+    }
+
+    // number of sprites drawn so far this frame
+    pub fn sprite_count(&self) -> usize {
+        self.transforms.len()
+    }
+
+    // copies this frame's widgets into `group`, resizing it to fit
+    pub fn end(&mut self, sprites: &mut SpriteRenderer, gpu: &WGPU, group: usize) {
+        // navigation nobody used this frame is dropped
+        self.nav.clear();
+        let count = self.transforms.len();
+        sprites.resize_sprite_group(gpu, group, count);
+        let (transforms, uvs) = sprites.get_sprites_mut(group);
+        transforms.copy_from_slice(&self.transforms);
+        uvs.copy_from_slice(&self.uvs);
+        sprites.upload_sprites(gpu, group, 0..count);
+    }
+
+    fn next_focusable(&mut self) -> bool {
+        let focused = self.focusable == self.focus;
+        self.focusable += 1;
+        focused
+    }
+
+    fn take_nav(&mut self, nav: UiNav) -> bool {
+        match self.nav.iter().position(|n| *n == nav) {
+            Some(idx) => {
+                self.nav.remove(idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn bar(&mut self, area: Rect, fraction: f32, focused: bool) {
+        let back = if focused {
+            self.skin.button_focused
+        } else {
+            self.skin.bar_back
+        };
+        self.push(area.into(), back.depth(WIDGET_DEPTH));
+        let fraction = fraction.clamp(0.0, 1.0);
+        if fraction > 0.0 {
+            let fill = Rect {
+                corner: area.corner,
+                size: Vec2 {
+                    x: area.size.x * fraction,
+                    y: area.size.y,
+                },
+            };
+            self.push(fill.into(), self.skin.bar_fill.depth(TEXT_DEPTH));
+        }
+    }
+
+    fn centered_text(&mut self, text: &str, area: Rect) {
+        let style = self.skin.text.align(Align::Center).depth(TEXT_DEPTH);
+        let height = self.skin.font.measure(text, &style).y;
+        let pos = Vec2 {
+            x: area.corner.x + area.size.x / 2.0,
+            y: area.corner.y + (area.size.y + height) / 2.0,
+        };
+        self.text(text, pos, &style);
+    }
+
+    fn text(&mut self, text: &str, pos: Vec2, style: &TextStyle) {
+        let start = self.transforms.len();
+        let count = self.skin.font.sprite_count(text);
+        self.transforms.resize(start + count, Transform::zeroed());
+        self.uvs.resize(start + count, SheetRegion::zeroed());
+        let drawn = self.skin.font.draw(
+            &mut self.transforms[start..],
+            &mut self.uvs[start..],
+            text,
+            pos,
+            style,
+        );
+        self.transforms.truncate(start + drawn);
+        self.uvs.truncate(start + drawn);
+    }
+
+    fn push(&mut self, transform: Transform, uv: SheetRegion) {
+        self.transforms.push(transform);
+        self.uvs.push(uv);
+    }
+}
@@ -0,0 +1,137 @@
+use engine::camera::{self, CameraController};
+use engine::geom::{Rect, Vec2};
+use engine::{Camera, Transform, Zeroable};
+
+const VIEW: Vec2 = Vec2 { x: 100.0, y: 200.0 };
+
+fn world() -> Rect {
+    Rect {
+        corner: Vec2::ZERO,
+        size: Vec2 {
+            x: 400.0,
+            y: 1000.0,
+        },
+    }
+}
+
+#[test]
+fn follow_waits_for_the_target_to_leave_the_deadzone() {
+    let mut camera = CameraController::new(VIEW).deadzone(Vec2 { x: 10.0, y: 20.0 });
+    let start = camera.center;
+    camera.follow(start + Vec2 { x: 10.0, y: -20.0 });
+    assert_eq!(camera.center, start);
+    // only the part outside the deadzone moves the camera
+    camera.follow(start + Vec2 { x: 15.0, y: 0.0 });
+    assert_eq!(camera.center, start + Vec2 { x: 5.0, y: 0.0 });
+}
+
+#[test]
+fn smoothing_covers_part_of_the_distance_each_tick() {
+    let mut camera = CameraController::new(VIEW).smoothing(0.5);
+    let start = camera.center;
+    let target = start + Vec2 { x: 0.0, y: 80.0 };
+    camera.follow(target);
+    assert_eq!(camera.center, start + Vec2 { x: 0.0, y: 40.0 });
+    camera.follow(target);
+    assert_eq!(camera.center, start + Vec2 { x: 0.0, y: 60.0 });
+    // smoothing past 1 still snaps rather than overshooting
+    let mut camera = CameraController::new(VIEW).smoothing(3.0);
+    camera.follow(target);
+    assert_eq!(camera.center, target);
+}
+
+#[test]
+fn view_stays_inside_the_world() {
+    let mut camera = CameraController::new(VIEW).bounds(world());
+    for (target, corner) in [
+        (
+            Vec2 {
+                x: -500.0,
+                y: -500.0,
+            },
+            Vec2 { x: 0.0, y: 0.0 },
+        ),
+        (
+            Vec2 {
+                x: 900.0,
+                y: 2000.0,
+            },
+            Vec2 { x: 300.0, y: 800.0 },
+        ),
+        (Vec2 { x: 200.0, y: 500.0 }, Vec2 { x: 150.0, y: 400.0 }),
+    ] {
+        camera.center = target;
+        assert_eq!(camera.view().corner, corner, "looking at {target}");
+        assert_eq!(camera.view().size, VIEW);
+    }
+}
+
+#[test]
+fn zoom_scales_the_view_around_its_center() {
+    let mut camera = CameraController::new(VIEW);
+    camera.center = Vec2 { x: 200.0, y: 300.0 };
+    camera.zoom = 2.0;
+    assert_eq!(
+        camera.view(),
+        Rect {
+            corner: Vec2 { x: 175.0, y: 250.0 },
+            size: VIEW / 2.0,
+        }
+    );
+
+    // zoomed out wider than the world, the view is centered across it
+    let mut camera = camera.bounds(world());
+    camera.zoom = 0.2;
+    let view = camera.view();
+    assert_eq!(view.size, VIEW * 5.0);
+    assert_eq!(view.corner, Vec2 { x: -50.0, y: 0.0 });
+}
+
+#[test]
+fn trauma_wears_off_to_no_shake() {
+    let mut camera = CameraController::new(VIEW);
+    camera.add_trauma(0.4);
+    camera.add_trauma(0.9);
+    assert_eq!(camera.trauma(), 1.0);
+    camera.tick();
+    assert!(camera.shake_offset() != Vec2::ZERO);
+    assert!(camera.shake_offset().length() <= camera.max_shake);
+
+    // a trauma of 1 decays at 1 a second, so it is gone after 60 ticks
+    for _ in 0..60 {
+        camera.tick();
+    }
+    assert_eq!(camera.trauma(), 0.0);
+    assert_eq!(camera.shake_offset(), Vec2::ZERO);
+    let view = Rect::from(camera.camera());
+    assert_eq!(view, camera.view());
+}
+
+#[test]
+fn screen_camera_ignores_movement_and_shake() {
+    let mut camera = CameraController::new(VIEW);
+    camera.center = Vec2 { x: 999.0, y: 999.0 };
+    camera.add_trauma(1.0);
+    camera.tick();
+    let screen = camera.screen_camera();
+    assert_eq!(screen.screen_pos, [0.0, 0.0]);
+    assert_eq!(screen.screen_size, [VIEW.x, VIEW.y]);
+}
+
+#[test]
+fn screen_sprites_follow_the_camera_into_the_world() {
+    let camera = Camera {
+        screen_pos: [50.0, 100.0],
+        screen_size: [50.0, 100.0],
+    };
+    let mut transforms = [Transform {
+        x: 20.0,
+        y: 40.0,
+        w: 10,
+        h: 8,
+        ..Transform::zeroed()
+    }];
+    camera::screen_to_world(camera, VIEW, &mut transforms);
+    assert_eq!((transforms[0].x, transforms[0].y), (60.0, 120.0));
+    assert_eq!((transforms[0].w, transforms[0].h), (5, 4));
+}
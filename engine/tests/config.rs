@@ -0,0 +1,78 @@
+use engine::config::{self, Config, ConfigError, Problems};
+use serde::Deserialize;
+use std::ops::Range;
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Tuning {
+    speed: f32,
+    lanes: Vec<f32>,
+    interval: Range<u32>,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Tuning {
+            speed: 4.0,
+            lanes: vec![100.0, 200.0],
+            interval: 30..90,
+        }
+    }
+}
+
+impl Config for Tuning {
+    fn validate(&self, problems: &mut Problems) {
+        problems.positive("speed", self.speed);
+        problems.not_empty("lanes", &self.lanes);
+        problems.ticks("interval", &self.interval);
+    }
+}
+
+#[test]
+fn omitted_values_keep_their_defaults() {
+    let tuning: Tuning = config::parse("speed = 2.5").unwrap();
+    assert_eq!(tuning.speed, 2.5);
+    assert_eq!(tuning.lanes, [100.0, 200.0]);
+    assert_eq!(tuning.interval, 30..90);
+
+    let tuning: Tuning = config::parse("").unwrap();
+    assert_eq!(tuning.speed, 4.0);
+}
+
+#[test]
+fn ranges_are_tables() {
+    let tuning: Tuning = config::parse("interval = { start = 10, end = 20 }").unwrap();
+    assert_eq!(tuning.interval, 10..20);
+}
+
+#[test]
+fn unknown_keys_are_rejected() {
+    match config::parse::<Tuning>("sped = 2.5") {
+        Err(ConfigError::Parse(err)) => assert!(err.to_string().contains("sped")),
+        other => panic!("expected a parse error, got {other:?}"),
+    }
+}
+
+#[test]
+fn wrong_types_are_rejected() {
+    assert!(matches!(
+        config::parse::<Tuning>("speed = \"fast\""),
+        Err(ConfigError::Parse(_))
+    ));
+}
+
+#[test]
+fn invalid_values_name_the_field() {
+    let err = config::parse::<Tuning>("interval = { start = 90, end = 30 }").unwrap_err();
+    let ConfigError::Invalid(problems) = &err else {
+        panic!("expected invalid values, got {err:?}");
+    };
+    let problems: Vec<&String> = problems.iter().collect();
+    assert_eq!(problems.len(), 1);
+    assert!(problems[0].starts_with("interval "), "{}", problems[0]);
+
+    // every bad value is reported at once
+    let err = config::parse::<Tuning>("speed = 0.0\nlanes = []").unwrap_err();
+    let text = err.to_string();
+    assert!(text.contains("speed") && text.contains("lanes"), "{text}");
+}
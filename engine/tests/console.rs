@@ -0,0 +1,162 @@
+use engine::console::{Command, Console};
+use engine::Key;
+
+fn new_console() -> Console {
+    let mut console = Console::default();
+    console.cvar("car_speed", 1.0f32, "how fast cars move");
+    console.cvar("cars", 3i64, "cars at once");
+    console.cvar("god_mode", false, "no crashing");
+    console.command("spawn", "spawn car|coin");
+    console.command("score", "score <n>");
+    console
+}
+
+fn type_line(console: &mut Console, line: &str) {
+    line.chars().for_each(|c| console.type_char(c));
+}
+
+fn last_output(console: &Console) -> &str {
+    console.output().last().map(String::as_str).unwrap_or("")
+}
+
+#[test]
+fn submit_sets_and_shows_cvars() {
+    let mut console = new_console();
+    console.submit("car_speed 2.5");
+    assert_eq!(console.get::<f32>("car_speed"), Some(2.5));
+    assert_eq!(last_output(&console), "car_speed = 2.5");
+
+    console.submit("  god_mode on ");
+    assert_eq!(console.get::<bool>("god_mode"), Some(true));
+    console.submit("cars");
+    assert_eq!(last_output(&console), "cars = 3");
+
+    // a value of the wrong type leaves the cvar alone
+    console.submit("cars lots");
+    assert_eq!(console.get::<i64>("cars"), Some(3));
+    assert_eq!(last_output(&console), "cars takes a int");
+}
+
+#[test]
+fn submit_hands_game_commands_back() {
+    let mut console = new_console();
+    console.submit("spawn coin");
+    console.submit("score");
+    console.submit("explode");
+    assert_eq!(
+        console.take_commands(),
+        [
+            Command {
+                name: "spawn".to_string(),
+                args: vec!["coin".to_string()],
+            },
+            Command {
+                name: "score".to_string(),
+                args: vec![],
+            },
+        ]
+    );
+    assert!(console.take_commands().is_empty());
+    assert_eq!(last_output(&console), "unknown command explode, try help");
+}
+
+#[test]
+fn reset_restores_defaults() {
+    let mut console = new_console();
+    console.submit("car_speed 9");
+    console.submit("cars 9");
+    console.submit("reset car_speed");
+    assert_eq!(console.get::<f32>("car_speed"), Some(1.0));
+    assert_eq!(console.get::<i64>("cars"), Some(9));
+    console.submit("reset");
+    assert_eq!(console.get::<i64>("cars"), Some(3));
+}
+
+#[test]
+fn return_submits_the_typed_line() {
+    let mut console = new_console();
+    console.set_open(true);
+    type_line(&mut console, "cars 5");
+    console.key(Key::Return);
+    assert_eq!(console.get::<i64>("cars"), Some(5));
+    assert_eq!(console.line(), "");
+
+    type_line(&mut console, "carz");
+    console.key(Key::Back);
+    assert_eq!(console.line(), "car");
+    console.key(Key::Escape);
+    assert!(!console.is_open());
+}
+
+#[test]
+fn history_steps_back_and_forward() {
+    let mut console = new_console();
+    // up with no history does nothing
+    console.key(Key::Up);
+    assert_eq!(console.line(), "");
+    for line in ["cars 1", "cars 2", "cars 2", "score"] {
+        console.submit(line);
+    }
+    // a repeated line is kept once
+    console.key(Key::Up);
+    assert_eq!(console.line(), "score");
+    console.key(Key::Up);
+    assert_eq!(console.line(), "cars 2");
+    console.key(Key::Up);
+    assert_eq!(console.line(), "cars 1");
+    // stops at the oldest
+    console.key(Key::Up);
+    assert_eq!(console.line(), "cars 1");
+    console.key(Key::Down);
+    assert_eq!(console.line(), "cars 2");
+    console.key(Key::Down);
+    console.key(Key::Down);
+    assert_eq!(console.line(), "");
+}
+
+#[test]
+fn history_is_bounded() {
+    let mut console = new_console();
+    console.history_len = 2;
+    for n in 0..5 {
+        console.submit(&format!("cars {n}"));
+    }
+    for _ in 0..5 {
+        console.key(Key::Up);
+    }
+    assert_eq!(console.line(), "cars 3");
+}
+
+#[test]
+fn tab_completes_unambiguous_names() {
+    let mut console = new_console();
+    type_line(&mut console, "sp");
+    console.key(Key::Tab);
+    assert_eq!(console.line(), "spawn ");
+
+    // after the first word only cvars are completed
+    let mut console = new_console();
+    type_line(&mut console, "reset go");
+    console.key(Key::Tab);
+    assert_eq!(console.line(), "reset god_mode ");
+}
+
+#[test]
+fn tab_lists_ambiguous_names() {
+    let mut console = new_console();
+    type_line(&mut console, "ca");
+    console.key(Key::Tab);
+    // completes as far as the choices agree, then lists them
+    assert_eq!(console.line(), "car");
+    assert_eq!(last_output(&console), "car_speed cars");
+
+    console.key(Key::Back);
+    console.key(Key::Back);
+    console.key(Key::Tab);
+    assert_eq!(last_output(&console), "car_speed cars clear cvars");
+
+    let mut console = new_console();
+    type_line(&mut console, "zz");
+    console.key(Key::Tab);
+    assert_eq!(console.line(), "zz");
+}
@@ -0,0 +1,102 @@
+use engine::coords::{self, Viewport};
+use engine::geom::Vec2;
+use engine::Camera;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+
+const SCREEN: Vec2 = Vec2 {
+    x: 768.0,
+    y: 1280.0,
+};
+
+// a 2x DPI window showing the game at its logical resolution
+fn viewport() -> Viewport {
+    Viewport::new(PhysicalSize::new(1536, 2560), 2.0)
+}
+
+// a view 200 units wide and tall, with its bottom-left corner at (100, 50)
+fn camera() -> Camera {
+    Camera {
+        screen_pos: [100.0, 50.0],
+        screen_size: [200.0, 200.0],
+    }
+}
+
+fn close(a: Vec2, b: Vec2) -> bool {
+    (a - b).length() < 1e-3
+}
+
+#[test]
+fn window_corners_map_to_screen_corners() {
+    let viewport = viewport();
+    let top_left = viewport.window_to_screen(PhysicalPosition::new(0.0, 0.0), SCREEN);
+    assert_eq!(top_left, Vec2 { x: 0.0, y: 1280.0 });
+    let bottom_right = viewport.window_to_screen(PhysicalPosition::new(1536.0, 2560.0), SCREEN);
+    assert_eq!(bottom_right, Vec2 { x: 768.0, y: 0.0 });
+    let middle = viewport.window_to_screen(PhysicalPosition::new(768.0, 1280.0), SCREEN);
+    assert_eq!(middle, SCREEN / 2.0);
+}
+
+#[test]
+fn window_and_world_round_trip() {
+    let viewport = viewport();
+    let camera = camera();
+    let window = PhysicalPosition::new(384.0, 640.0);
+    let world = viewport.window_to_world(window, camera);
+    // a quarter of the way across and three quarters of the way up the view
+    assert_eq!(world, Vec2 { x: 150.0, y: 200.0 });
+    let back = viewport.world_to_window(world, camera);
+    assert_eq!((back.x, back.y), (384.0, 640.0));
+
+    for pos in [Vec2::ZERO, Vec2 { x: 123.0, y: 456.0 }, SCREEN] {
+        let window = viewport.screen_to_window(pos, SCREEN);
+        assert!(close(viewport.window_to_screen(window, SCREEN), pos));
+    }
+}
+
+#[test]
+fn screen_and_world_round_trip() {
+    let camera = camera();
+    let world = coords::screen_to_world(camera, SCREEN, SCREEN / 2.0);
+    assert_eq!(world, Vec2 { x: 200.0, y: 150.0 });
+    assert_eq!(coords::world_to_screen(camera, SCREEN, world), SCREEN / 2.0);
+}
+
+#[test]
+fn scale_factor_converts_logical_and_physical_pixels() {
+    let viewport = viewport();
+    assert_eq!(viewport.logical_size(), SCREEN);
+    let logical = viewport.physical_to_logical(PhysicalPosition::new(300.0, 90.0));
+    assert_eq!(logical, Vec2 { x: 150.0, y: 45.0 });
+    let physical = viewport.logical_to_physical(logical);
+    assert_eq!((physical.x, physical.y), (300.0, 90.0));
+}
+
+#[test]
+fn resizing_stretches_the_view_over_the_new_window() {
+    let mut viewport = viewport();
+    let camera = camera();
+    // a wider window has no letterbox: its edges are still the view's edges
+    viewport.resize(PhysicalSize::new(3000, 2560));
+    let right = viewport.window_to_world(PhysicalPosition::new(3000.0, 2560.0), camera);
+    assert_eq!(right, Vec2 { x: 300.0, y: 50.0 });
+    let middle = viewport.window_to_screen(PhysicalPosition::new(1500.0, 1280.0), SCREEN);
+    assert_eq!(middle, SCREEN / 2.0);
+
+    // moving to a 1x display halves the window but not the logical size
+    viewport.rescale(1.0, PhysicalSize::new(768, 1280));
+    assert_eq!(viewport.logical_size(), SCREEN);
+    let middle = viewport.window_to_screen(PhysicalPosition::new(384.0, 640.0), SCREEN);
+    assert_eq!(middle, SCREEN / 2.0);
+    assert_eq!(
+        viewport.physical_to_logical(PhysicalPosition::new(10.0, 20.0)),
+        Vec2 { x: 10.0, y: 20.0 }
+    );
+}
+
+#[test]
+fn minimized_window_does_not_divide_by_zero() {
+    let mut viewport = viewport();
+    viewport.resize(PhysicalSize::new(0, 0));
+    let pos = viewport.window_to_world(PhysicalPosition::new(0.0, 0.0), camera());
+    assert!(pos.is_finite());
+}
@@ -0,0 +1,79 @@
+use engine::difficulty::{Curve, Difficulty, Measure};
+use engine::ease::Ease;
+
+#[test]
+fn curve_interpolates_between_points_and_holds_outside_them() {
+    let curve = Curve::new(vec![(10.0, 2.0), (20.0, 4.0), (40.0, 0.0)]);
+    assert_eq!(curve.sample(0.0), 2.0);
+    assert_eq!(curve.sample(10.0), 2.0);
+    assert_eq!(curve.sample(15.0), 3.0);
+    assert_eq!(curve.sample(20.0), 4.0);
+    assert_eq!(curve.sample(30.0), 2.0);
+    // the last point is the cap
+    assert_eq!(curve.sample(40.0), 0.0);
+    assert_eq!(curve.sample(1000.0), 0.0);
+}
+
+#[test]
+fn eased_curve_bends_between_points() {
+    let linear = Curve::ramp(0.0, 100.0, 10.0);
+    let eased = Curve::ramp(0.0, 100.0, 10.0).eased(Ease::QuadIn);
+    assert_eq!(linear.sample(5.0), 50.0);
+    assert_eq!(eased.sample(5.0), 25.0);
+    // the points themselves don't move
+    assert_eq!(eased.sample(0.0), 0.0);
+    assert_eq!(eased.sample(10.0), 100.0);
+}
+
+#[test]
+fn degenerate_curves() {
+    assert_eq!(Curve::constant(3.0).sample(-5.0), 3.0);
+    assert_eq!(Curve::constant(3.0).sample(99.0), 3.0);
+    assert_eq!(Curve::new(vec![]).sample(1.0), 0.0);
+}
+
+#[test]
+fn time_difficulty_counts_ticks() {
+    let mut difficulty = Difficulty::new(Measure::Time, 30.0, 3);
+    let speed = Curve::ramp(4.0, 8.0, 60.0);
+    assert_eq!(difficulty.level(), 1);
+    assert_eq!(difficulty.sample(&speed), 4.0);
+    // score doesn't move a time-based difficulty
+    difficulty.set_score(1000);
+    assert_eq!(difficulty.progress(), 0.0);
+
+    // 30 seconds at 60 ticks a second
+    for _ in 0..1800 {
+        difficulty.tick();
+    }
+    assert!((difficulty.elapsed() - 30.0).abs() < 1e-3);
+    assert_eq!(difficulty.level(), 2);
+    assert!((difficulty.sample(&speed) - 6.0).abs() < 1e-3);
+
+    // levels stop at the max
+    for _ in 0..18000 {
+        difficulty.tick();
+    }
+    assert_eq!(difficulty.level(), 3);
+    assert_eq!(difficulty.sample(&speed), 8.0);
+}
+
+#[test]
+fn score_difficulty_follows_the_score() {
+    let mut difficulty = Difficulty::new(Measure::Score, 10.0, 5);
+    let spawn_every = Curve::new(vec![(0.0, 90.0), (40.0, 30.0)]);
+    difficulty.tick();
+    assert_eq!(difficulty.progress(), 0.0);
+    for (score, level, sample) in [(9, 1, 76.5), (10, 2, 75.0), (25, 3, 52.5), (70, 5, 30.0)] {
+        difficulty.set_score(score);
+        assert_eq!(difficulty.level(), level, "score {score}");
+        assert_eq!(difficulty.sample(&spawn_every), sample, "score {score}");
+    }
+}
+
+#[test]
+fn no_level_step_stays_at_level_one() {
+    let mut difficulty = Difficulty::new(Measure::Score, 0.0, 5);
+    difficulty.set_score(100);
+    assert_eq!(difficulty.level(), 1);
+}
@@ -0,0 +1,73 @@
+use engine::ecs::{Collider, World};
+use engine::geom::Vec2;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Kind {
+    Car,
+    Coin,
+}
+
+fn at(y: f32) -> Vec2 {
+    Vec2 { x: 0.0, y }
+}
+
+#[test]
+fn despawn_waits_for_flush() {
+    let mut world = World::default();
+    let car = world.spawn(Kind::Car, at(1.0));
+    let coin = world.spawn(Kind::Coin, at(2.0));
+    world.despawn(car);
+    world.despawn(car);
+    // still there until the flush, e.g. for the rest of a loop over entities
+    assert!(world.is_alive(car));
+    assert_eq!(world.kind(car), Some(&Kind::Car));
+    assert_eq!(world.len(), 2);
+
+    world.flush();
+    assert!(!world.is_alive(car));
+    assert_eq!(world.kind(car), None);
+    assert_eq!(world.pos(car), None);
+    assert!(world.is_alive(coin));
+    assert_eq!(world.len(), 1);
+}
+
+#[test]
+fn stale_ids_miss_the_reused_slot() {
+    let mut world = World::default();
+    let car = world.spawn(Kind::Car, at(1.0));
+    world.colliders.insert(car, Collider { radius: 4.0 });
+    world.despawn(car);
+    world.flush();
+
+    let coin = world.spawn(Kind::Coin, at(2.0));
+    assert_ne!(coin, car);
+    assert!(!world.is_alive(car));
+    assert_eq!(world.kind(car), None);
+    assert_eq!(world.kind(coin), Some(&Kind::Coin));
+    // components added through a stale id don't land on the new entity
+    world.colliders.insert(car, Collider { radius: 9.0 });
+    assert_eq!(world.colliders.get(coin), None);
+    world.despawn(car);
+    world.flush();
+    assert!(world.is_alive(coin));
+}
+
+#[test]
+fn ids_from_before_clear_stay_stale() {
+    let mut world = World::default();
+    let old: Vec<_> = (0..3).map(|i| world.spawn(Kind::Car, at(i as f32))).collect();
+    world.despawn(old[0]);
+    world.clear();
+    assert!(world.is_empty());
+
+    let new: Vec<_> = (0..4).map(|i| world.spawn(Kind::Coin, at(i as f32))).collect();
+    for entity in old.iter() {
+        assert!(!world.is_alive(*entity));
+        assert_eq!(world.kind(*entity), None);
+        assert!(!new.contains(entity));
+    }
+    // a despawn queued before the clear doesn't remove the slot's new entity
+    world.flush();
+    assert_eq!(world.len(), 4);
+    assert!(new.iter().all(|e| world.is_alive(*e)));
+}
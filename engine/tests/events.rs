@@ -0,0 +1,126 @@
+use engine::events::EventBus;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Event {
+    Coin,
+    Crash,
+    // counts down, emitting the next number until it reaches 0
+    Chain(u32),
+    // emits itself again every time it is delivered
+    Echo,
+}
+
+#[derive(Default)]
+struct Game {
+    events: EventBus<Event, Game>,
+    seen: Vec<(&'static str, Event)>,
+}
+
+impl Game {
+    fn score(&mut self, event: &Event) {
+        self.seen.push(("score", *event));
+        match event {
+            Event::Chain(n) if *n > 0 => self.events.emit(Event::Chain(n - 1)),
+            Event::Echo => self.events.emit(Event::Echo),
+            _ => {}
+        }
+    }
+
+    fn sound(&mut self, event: &Event) {
+        self.seen.push(("sound", *event));
+    }
+
+    fn dispatch(&mut self) {
+        EventBus::dispatch(self, |game| &mut game.events);
+    }
+}
+
+fn game() -> Game {
+    let mut game = Game::default();
+    game.events.subscribe(Game::score);
+    game.events.subscribe(Game::sound);
+    game
+}
+
+#[test]
+fn events_reach_subscribers_in_order() {
+    let mut game = game();
+    game.events.emit(Event::Crash);
+    game.events.emit(Event::Coin);
+    assert_eq!(game.events.pending(), [Event::Crash, Event::Coin]);
+    game.dispatch();
+    assert_eq!(
+        game.seen,
+        [
+            ("score", Event::Crash),
+            ("sound", Event::Crash),
+            ("score", Event::Coin),
+            ("sound", Event::Coin),
+        ]
+    );
+    assert!(game.events.pending().is_empty());
+
+    // nothing is delivered twice
+    game.dispatch();
+    assert_eq!(game.seen.len(), 4);
+}
+
+#[test]
+fn events_emitted_while_dispatching_go_out_in_the_same_dispatch() {
+    let mut game = game();
+    game.events.emit(Event::Chain(2));
+    game.events.emit(Event::Coin);
+    game.dispatch();
+    // the chain's next link comes after the event emitted before it
+    let scored: Vec<Event> = game
+        .seen
+        .iter()
+        .filter(|(by, _)| *by == "score")
+        .map(|(_, event)| *event)
+        .collect();
+    assert_eq!(
+        scored,
+        [
+            Event::Chain(2),
+            Event::Coin,
+            Event::Chain(1),
+            Event::Chain(0)
+        ]
+    );
+    assert!(game.events.pending().is_empty());
+}
+
+#[test]
+fn endless_events_are_left_for_the_next_dispatch() {
+    let mut game = game();
+    game.events.emit(Event::Echo);
+    game.dispatch();
+    // a bounded number of rounds went out, and the next echo waits
+    let rounds = game.seen.len() / 2;
+    assert_eq!(rounds, 16);
+    assert_eq!(game.events.pending(), [Event::Echo]);
+    game.dispatch();
+    assert_eq!(game.seen.len() / 2, 2 * rounds);
+
+    game.events.clear();
+    game.dispatch();
+    assert_eq!(game.seen.len() / 2, 2 * rounds);
+}
+
+#[test]
+fn subscribers_added_while_dispatching_wait_for_the_next_dispatch() {
+    fn subscribe_late(game: &mut Game, _event: &Event) {
+        if game.seen.is_empty() {
+            game.events.subscribe(Game::sound);
+        }
+        game.seen.push(("late", Event::Coin));
+    }
+    let mut game = Game::default();
+    game.events.subscribe(subscribe_late);
+    game.events.emit(Event::Crash);
+    game.dispatch();
+    assert_eq!(game.seen, [("late", Event::Coin)]);
+    game.events.emit(Event::Crash);
+    game.dispatch();
+    assert_eq!(game.seen.len(), 3);
+}
@@ -0,0 +1,111 @@
+use engine::ease::Ease;
+use engine::geom::Vec2;
+use engine::particles::{Emitter, Particles};
+use engine::{SheetRegion, Transform, Zeroable};
+
+fn frames() -> Vec<SheetRegion> {
+    vec![
+        SheetRegion::new(0, 0, 0, 0, 8, 8),
+        SheetRegion::new(0, 8, 0, 0, 8, 8),
+    ]
+}
+
+fn sparks(seed: u64) -> Particles {
+    let mut particles = Particles::new(seed);
+    particles.add(
+        Emitter::new(frames())
+            .rate(30.0)
+            .lifetime(20..40)
+            .gravity(Vec2 { x: 0.0, y: -200.0 }),
+    );
+    particles.add(Emitter::new(frames()).size(Vec2::splat(4.0), Vec2::ZERO, Ease::QuadOut));
+    particles
+}
+
+// where every particle is and what it shows
+fn drawn(particles: &Particles) -> Vec<(f32, f32, u16, u16, u16)> {
+    let count = particles.sprite_count();
+    let mut transforms = vec![Transform::zeroed(); count];
+    let mut uvs = vec![SheetRegion::zeroed(); count];
+    assert_eq!(particles.write_sprites(&mut transforms, &mut uvs), count);
+    transforms
+        .iter()
+        .zip(uvs.iter())
+        .map(|(t, uv)| (t.x, t.y, t.w, t.h, uv.x))
+        .collect()
+}
+
+#[test]
+fn same_seed_plays_the_same() {
+    let mut a = sparks(9);
+    let mut b = sparks(9);
+    let mut other = sparks(10);
+    for tick in 0..90 {
+        if tick % 20 == 0 {
+            let pos = Vec2 {
+                x: tick as f32,
+                y: 100.0,
+            };
+            for particles in [&mut a, &mut b, &mut other] {
+                particles.burst(1, pos, 6);
+            }
+        }
+        a.update();
+        b.update();
+        other.update();
+        assert_eq!(drawn(&a), drawn(&b));
+    }
+    assert!(a.sprite_count() > 0);
+    assert_ne!(drawn(&a), drawn(&other));
+}
+
+#[test]
+fn particles_die_after_their_lifetime() {
+    let mut particles = Particles::new(1);
+    let emitter = particles.add(Emitter::new(frames()).lifetime(10..11));
+    particles.burst(emitter, Vec2::ZERO, 5);
+    for _ in 0..9 {
+        particles.update();
+    }
+    assert_eq!(particles.emitters[emitter].particle_count(), 5);
+    // the second half of a particle's life shows the second frame
+    assert!(drawn(&particles).iter().all(|p| p.4 == 8));
+    particles.update();
+    assert_eq!(particles.sprite_count(), 0);
+}
+
+#[test]
+fn rate_emits_steadily() {
+    let mut particles = Particles::new(1);
+    // one particle every other tick, living long enough to count them all
+    particles.add(Emitter::new(frames()).rate(30.0).lifetime(100..101));
+    for _ in 0..60 {
+        particles.update();
+    }
+    assert_eq!(particles.sprite_count(), 30);
+    particles.clear();
+    assert_eq!(particles.sprite_count(), 0);
+}
+
+#[test]
+fn gravity_accelerates_particles() {
+    let mut particles = Particles::new(1);
+    // particles start still, so only gravity moves them
+    let emitter = particles.add(
+        Emitter::new(frames())
+            .speed(0.0..0.0)
+            .lifetime(100..101)
+            .gravity(Vec2 { x: 0.0, y: -60.0 }),
+    );
+    particles.burst(emitter, Vec2 { x: 5.0, y: 0.0 }, 1);
+    let mut fallen = Vec::new();
+    for _ in 0..3 {
+        particles.update();
+        fallen.push(-drawn(&particles)[0].1);
+    }
+    assert_eq!(drawn(&particles)[0].0, 5.0);
+    // each tick falls one unit further than the last: 1/60, 3/60, 6/60
+    for (fallen, expected) in fallen.iter().zip([1.0, 3.0, 6.0]) {
+        assert!((fallen - expected / 60.0).abs() < 1e-5, "{fallen}");
+    }
+}
@@ -0,0 +1,175 @@
+use engine::coords::Viewport;
+use engine::pointer::{Gesture, Pointer, Source, SwipeDir};
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::event::{
+    DeviceId, ElementState, Event, ModifiersState, MouseButton, Touch, TouchPhase, WindowEvent,
+};
+use winit::window::WindowId;
+
+fn window_event(event: WindowEvent<'static>) -> Event<'static, ()> {
+    // the ids are only compared, never passed back to winit
+    Event::WindowEvent {
+        window_id: unsafe { WindowId::dummy() },
+        event,
+    }
+}
+
+fn touch(phase: TouchPhase, id: u64, x: f64, y: f64) -> Event<'static, ()> {
+    window_event(WindowEvent::Touch(Touch {
+        device_id: unsafe { DeviceId::dummy() },
+        phase,
+        location: PhysicalPosition::new(x, y),
+        force: None,
+        id,
+    }))
+}
+
+// drags a finger from `from` to `to` over `ticks` ticks, ending on the last
+fn drag(pointer: &mut Pointer, viewport: &Viewport, from: (f64, f64), to: (f64, f64), ticks: u32) {
+    pointer.process_input_event(&touch(TouchPhase::Started, 1, from.0, from.1), viewport);
+    for tick in 1..=ticks {
+        let t = tick as f64 / ticks as f64;
+        let (x, y) = (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+        if tick < ticks {
+            pointer.process_input_event(&touch(TouchPhase::Moved, 1, x, y), viewport);
+            pointer.next_frame();
+        } else {
+            pointer.process_input_event(&touch(TouchPhase::Ended, 1, x, y), viewport);
+        }
+    }
+}
+
+fn viewport(scale_factor: f64) -> Viewport {
+    Viewport::new(PhysicalSize::new(768, 1280), scale_factor)
+}
+
+#[test]
+fn quick_still_press_is_a_tap() {
+    let (mut pointer, viewport) = (Pointer::default(), viewport(1.0));
+    drag(&mut pointer, &viewport, (100.0, 200.0), (105.0, 203.0), 5);
+    assert!(pointer.is_tapped());
+    assert_eq!(
+        pointer.gestures(),
+        [Gesture::Tap(PhysicalPosition::new(105.0, 203.0))]
+    );
+    // gestures last one tick
+    pointer.next_frame();
+    assert!(!pointer.is_tapped());
+}
+
+#[test]
+fn long_press_is_not_a_tap() {
+    let (mut pointer, viewport) = (Pointer::default(), viewport(1.0));
+    drag(&mut pointer, &viewport, (100.0, 200.0), (100.0, 200.0), 40);
+    assert!(pointer.gestures().is_empty());
+}
+
+#[test]
+fn swipes_report_their_direction() {
+    let viewport = viewport(1.0);
+    // window y points down, so dragging toward the top is a swipe up
+    for (to, dir) in [
+        ((300.0, 400.0), SwipeDir::Right),
+        ((100.0, 400.0), SwipeDir::Left),
+        ((200.0, 300.0), SwipeDir::Up),
+        ((200.0, 500.0), SwipeDir::Down),
+        // mostly sideways counts as sideways
+        ((280.0, 360.0), SwipeDir::Right),
+    ] {
+        let mut pointer = Pointer::default();
+        drag(&mut pointer, &viewport, (200.0, 400.0), to, 10);
+        assert_eq!(pointer.gestures(), [Gesture::Swipe(dir)], "to {to:?}");
+        assert!(pointer.swiped(dir));
+    }
+}
+
+#[test]
+fn slow_or_short_drags_are_not_swipes() {
+    let viewport = viewport(1.0);
+    let mut pointer = Pointer::default();
+    drag(&mut pointer, &viewport, (200.0, 400.0), (400.0, 400.0), 60);
+    assert!(pointer.gestures().is_empty());
+    // between a tap and a swipe
+    drag(&mut pointer, &viewport, (200.0, 400.0), (230.0, 400.0), 5);
+    assert!(pointer.gestures().is_empty());
+}
+
+#[test]
+fn distances_are_in_logical_pixels() {
+    // 60 physical pixels is a swipe at 1x but only 30 logical pixels at 2x
+    let mut pointer = Pointer::default();
+    drag(
+        &mut pointer,
+        &viewport(1.0),
+        (200.0, 400.0),
+        (260.0, 400.0),
+        5,
+    );
+    assert!(pointer.swiped(SwipeDir::Right));
+    let mut pointer = Pointer::default();
+    drag(
+        &mut pointer,
+        &viewport(2.0),
+        (200.0, 400.0),
+        (260.0, 400.0),
+        5,
+    );
+    assert!(pointer.gestures().is_empty());
+    // and 20 physical pixels is a tap at 2x
+    drag(
+        &mut pointer,
+        &viewport(2.0),
+        (200.0, 400.0),
+        (220.0, 400.0),
+        5,
+    );
+    assert!(pointer.is_tapped());
+}
+
+#[test]
+fn fingers_are_tracked_separately() {
+    let (mut pointer, viewport) = (Pointer::default(), viewport(1.0));
+    pointer.process_input_event(&touch(TouchPhase::Started, 1, 10.0, 10.0), &viewport);
+    pointer.process_input_event(&touch(TouchPhase::Started, 2, 500.0, 500.0), &viewport);
+    assert_eq!(pointer.presses().len(), 2);
+    pointer.process_input_event(&touch(TouchPhase::Moved, 2, 500.0, 300.0), &viewport);
+    pointer.process_input_event(&touch(TouchPhase::Ended, 2, 500.0, 300.0), &viewport);
+    assert!(pointer.swiped(SwipeDir::Up));
+    assert_eq!(pointer.presses()[0].source, Source::Touch(1));
+
+    // a touch the system takes over is dropped without a gesture
+    pointer.next_frame();
+    pointer.process_input_event(&touch(TouchPhase::Cancelled, 1, 10.0, 10.0), &viewport);
+    assert!(pointer.presses().is_empty());
+    assert!(pointer.gestures().is_empty());
+}
+
+#[test]
+#[allow(deprecated)]
+fn mouse_clicks_tap_where_the_cursor_is() {
+    let (mut pointer, viewport) = (Pointer::default(), viewport(1.0));
+    let device_id = unsafe { DeviceId::dummy() };
+    let click = |state| {
+        window_event(WindowEvent::MouseInput {
+            device_id,
+            state,
+            button: MouseButton::Left,
+            modifiers: ModifiersState::empty(),
+        })
+    };
+    pointer.process_input_event(
+        &window_event(WindowEvent::CursorMoved {
+            device_id,
+            position: PhysicalPosition::new(40.0, 50.0),
+            modifiers: ModifiersState::empty(),
+        }),
+        &viewport,
+    );
+    pointer.process_input_event(&click(ElementState::Pressed), &viewport);
+    assert_eq!(pointer.presses()[0].source, Source::Mouse);
+    pointer.process_input_event(&click(ElementState::Released), &viewport);
+    assert_eq!(
+        pointer.gestures(),
+        [Gesture::Tap(PhysicalPosition::new(40.0, 50.0))]
+    );
+}
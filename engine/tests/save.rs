@@ -0,0 +1,122 @@
+use engine::save::{load_json, save_json, write_atomic, HighScores, ScoreEntry};
+use std::fs;
+use std::path::PathBuf;
+
+// an empty folder of its own for each test
+fn temp_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("engine-save-{}-{test}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn entry(name: &str, score: u32) -> ScoreEntry {
+    ScoreEntry::new(name, score, 7)
+}
+
+#[test]
+fn write_keeps_a_backup_of_the_last_save() {
+    let dir = temp_dir("backup");
+    let path = dir.join("save.json");
+    write_atomic(&path, b"first").unwrap();
+    assert!(!dir.join("save.json.bak").exists());
+    write_atomic(&path, b"second").unwrap();
+    assert_eq!(fs::read(&path).unwrap(), b"second");
+    assert_eq!(fs::read(dir.join("save.json.bak")).unwrap(), b"first");
+    assert!(!dir.join("save.json.tmp").exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn missing_save_loads_nothing() {
+    let dir = temp_dir("missing");
+    assert_eq!(load_json::<Vec<u32>>(&dir.join("save.json")), None);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn corrupt_save_falls_back_to_backup() {
+    let dir = temp_dir("corrupt");
+    let path = dir.join("save.json");
+    save_json(&path, &vec![1, 2, 3]).unwrap();
+    save_json(&path, &vec![4, 5]).unwrap();
+    fs::write(&path, b"{ not json").unwrap();
+
+    assert_eq!(load_json::<Vec<u32>>(&path), Some(vec![1, 2, 3]));
+    // the broken file is kept aside rather than overwritten by the next save
+    assert!(!path.exists());
+    assert_eq!(fs::read(dir.join("save.json.corrupt")).unwrap(), b"{ not json");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn corrupt_save_without_backup_loads_nothing() {
+    let dir = temp_dir("corrupt-only");
+    let path = dir.join("save.json");
+    fs::write(&path, b"[1, 2").unwrap();
+    assert_eq!(load_json::<Vec<u32>>(&path), None);
+    assert!(dir.join("save.json.corrupt").exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn high_scores_rank_best_first_and_keep_ten() {
+    let dir = temp_dir("ranking");
+    let mut scores = HighScores::load_from(dir.join("high_scores.json"));
+    for score in [50, 10, 90, 30, 70, 20, 80, 40, 60, 100] {
+        assert!(scores.submit(entry("a", score)).is_some());
+    }
+    assert_eq!(scores.entries().len(), 10);
+    assert!(!scores.qualifies(10));
+    assert_eq!(scores.submit(entry("b", 5)), None);
+    assert_eq!(scores.submit(entry("b", 10)), None);
+
+    assert_eq!(scores.submit(entry("b", 75)), Some(3));
+    let ranked: Vec<u32> = scores.entries().iter().map(|e| e.score).collect();
+    assert_eq!(ranked, [100, 90, 80, 75, 70, 60, 50, 40, 30, 20]);
+
+    // earlier entries win ties
+    assert_eq!(scores.submit(entry("c", 80)), Some(3));
+    assert_eq!(scores.entries()[2].name, "a");
+    assert_eq!(scores.top(3).len(), 3);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn high_scores_survive_saving() {
+    let dir = temp_dir("reload");
+    let path = dir.join("high_scores.json");
+    let mut scores = HighScores::load_from(&path);
+    scores.submit(entry("a", 3));
+    scores.submit(entry("b", 9));
+    scores.save().unwrap();
+
+    let loaded = HighScores::load_from(&path);
+    assert_eq!(loaded.entries(), scores.entries());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn loading_sorts_and_trims_an_edited_table() {
+    let dir = temp_dir("edited");
+    let path = dir.join("high_scores.json");
+    let entries: Vec<ScoreEntry> = (1..=12).map(|score| entry("a", score)).collect();
+    save_json(&path, &entries).unwrap();
+
+    let loaded = HighScores::load_from(&path);
+    let ranked: Vec<u32> = loaded.entries().iter().map(|e| e.score).collect();
+    assert_eq!(ranked, [12, 11, 10, 9, 8, 7, 6, 5, 4, 3]);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn dates_are_shown_as_utc_days() {
+    let mut entry = entry("a", 1);
+    entry.date = 0;
+    assert_eq!(entry.date_string(), "1970-01-01");
+    // 2024-02-29 23:59:59, a leap day
+    entry.date = 1_709_251_199;
+    assert_eq!(entry.date_string(), "2024-02-29");
+    entry.date += 1;
+    assert_eq!(entry.date_string(), "2024-03-01");
+}
@@ -0,0 +1,99 @@
+use engine::scheduler::Scheduler;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Event {
+    Once,
+    Repeat,
+}
+
+// the tick numbers, counting from 1, on which each event fired
+fn run(scheduler: &mut Scheduler<Event>, ticks: u32) -> Vec<(u32, Event)> {
+    (1..=ticks)
+        .flat_map(|tick| scheduler.tick().into_iter().map(move |e| (tick, e)))
+        .collect()
+}
+
+#[test]
+fn after_fires_once() {
+    let mut scheduler = Scheduler::default();
+    let id = scheduler.after(3, Event::Once);
+    assert_eq!(scheduler.remaining(id), Some(3));
+    assert_eq!(run(&mut scheduler, 10), [(3, Event::Once)]);
+    assert!(!scheduler.is_pending(id));
+    assert!(scheduler.is_empty());
+}
+
+#[test]
+fn every_repeats_until_cancelled() {
+    let mut scheduler = Scheduler::default();
+    let id = scheduler.every(4, Event::Repeat);
+    let fired = run(&mut scheduler, 12);
+    assert_eq!(
+        fired,
+        [(4, Event::Repeat), (8, Event::Repeat), (12, Event::Repeat)]
+    );
+    assert!(scheduler.is_pending(id));
+    assert!(scheduler.cancel(id));
+    assert!(run(&mut scheduler, 12).is_empty());
+    assert!(!scheduler.cancel(id));
+}
+
+#[test]
+fn cancelled_timer_never_fires() {
+    let mut scheduler = Scheduler::default();
+    let once = scheduler.after(5, Event::Once);
+    scheduler.every(2, Event::Repeat);
+    run(&mut scheduler, 3);
+    assert!(scheduler.cancel(once));
+    let fired = run(&mut scheduler, 10);
+    assert!(fired.iter().all(|(_, e)| *e == Event::Repeat));
+    assert_eq!(scheduler.len(), 1);
+}
+
+#[test]
+fn same_tick_fires_in_scheduling_order() {
+    let mut scheduler = Scheduler::default();
+    scheduler.every(2, Event::Repeat);
+    scheduler.after(2, Event::Once);
+    assert_eq!(run(&mut scheduler, 2), [(2, Event::Repeat), (2, Event::Once)]);
+}
+
+#[test]
+fn paused_scheduler_and_timers_hold() {
+    let mut scheduler = Scheduler::default();
+    let once = scheduler.after(3, Event::Once);
+    scheduler.every(3, Event::Repeat);
+    scheduler.set_paused(true);
+    assert!(run(&mut scheduler, 10).is_empty());
+    assert_eq!(scheduler.remaining(once), Some(3));
+    scheduler.set_paused(false);
+
+    // pausing one timer leaves the others running
+    scheduler.pause(once);
+    assert_eq!(run(&mut scheduler, 6), [(3, Event::Repeat), (6, Event::Repeat)]);
+    assert_eq!(scheduler.remaining(once), Some(3));
+    scheduler.resume(once);
+    assert_eq!(run(&mut scheduler, 3), [(3, Event::Once), (3, Event::Repeat)]);
+}
+
+#[test]
+fn time_scale_changes_when_timers_fire() {
+    let mut scheduler = Scheduler::default();
+    scheduler.time_scale = 0.5;
+    scheduler.after(3, Event::Once);
+    assert_eq!(run(&mut scheduler, 10), [(6, Event::Once)]);
+
+    // a short period at a fast time scale fires more than once a tick
+    let mut scheduler = Scheduler::default();
+    scheduler.time_scale = 2.0;
+    scheduler.every(1, Event::Repeat);
+    assert_eq!(run(&mut scheduler, 2).len(), 4);
+
+    // changing the scale part way carries the time already counted down
+    let mut scheduler = Scheduler::default();
+    let id = scheduler.after(10, Event::Once);
+    run(&mut scheduler, 4);
+    scheduler.time_scale = 2.0;
+    assert_eq!(scheduler.remaining(id), Some(6));
+    assert_eq!(run(&mut scheduler, 5), [(3, Event::Once)]);
+}
@@ -0,0 +1,207 @@
+use engine::ecs::{Collider, World};
+use engine::geom::Vec2;
+use engine::rng::GameRng;
+use engine::scheduler::Scheduler;
+use engine::snapshot::{self, Snapshot, SnapshotError};
+use engine::spawner::{Placement, SpawnRule, Spawner};
+use engine::Engine;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+enum Kind {
+    Car,
+    Coin,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+enum Event {
+    Bonus,
+    Siren,
+}
+
+// everything a run of one of the games changes as it plays, in the engine's
+// own types
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Run {
+    things: World<Kind>,
+    spawner: Spawner<Kind>,
+    timers: Scheduler<Event>,
+    rng: GameRng,
+    score: u32,
+    sirens: u32,
+}
+
+impl Run {
+    fn new() -> Self {
+        let mut spawner = Spawner::default();
+        spawner.add(
+            SpawnRule::new(
+                vec![(Kind::Car, 3.0), (Kind::Coin, 1.0)],
+                Placement::Lanes(vec![100.0, 200.0, 300.0]),
+                600.0,
+            )
+            .interval(5..20)
+            .min_spacing(30.0),
+        );
+        let mut timers = Scheduler::default();
+        timers.every(7, Event::Bonus);
+        timers.after(40, Event::Siren);
+        Run {
+            things: World::default(),
+            spawner,
+            timers,
+            rng: GameRng::new(42),
+            score: 0,
+            sirens: 0,
+        }
+    }
+
+    // one tick of play; returns what a player would see change
+    fn update(&mut self) -> (Vec<(Kind, Vec2)>, u32) {
+        self.spawner
+            .update(&mut self.things, &mut self.rng, |world, kind, pos| {
+                let entity = world.spawn(kind, pos);
+                world.velocities.insert(entity, Vec2 { x: 0.0, y: -9.0 });
+                world.colliders.insert(entity, Collider { radius: 10.0 });
+                entity
+            });
+        self.things.move_all(|_| 1.0);
+        self.things.cull(|pos| pos.y < 0.0);
+        for event in self.timers.tick() {
+            match event {
+                Event::Bonus => self.score += self.rng.gen_range(1..10),
+                Event::Siren => {
+                    self.sirens += 1;
+                    self.timers.after(self.rng.gen_range(10..30), Event::Siren);
+                }
+            }
+        }
+        let things = self
+            .things
+            .kinds
+            .iter()
+            .map(|(entity, kind)| (*kind, self.things.pos(entity).unwrap()))
+            .collect();
+        (things, self.rng.next_u32())
+    }
+}
+
+impl Snapshot for Run {
+    type State = Run;
+    const NAME: &'static str = "run";
+
+    fn snapshot(&self) -> Run {
+        self.clone()
+    }
+
+    fn restore(&mut self, _engine: &mut Engine, state: Run) {
+        *self = state;
+    }
+}
+
+// state saved by another game, and by an older version of this one before
+// `Run` had its current shape
+#[derive(Serialize, Deserialize)]
+struct OldState {
+    score: f32,
+    cars: Vec<Vec2>,
+}
+
+struct OtherGame;
+struct OldRun;
+
+impl Snapshot for OtherGame {
+    type State = OldState;
+    const NAME: &'static str = "other";
+
+    fn snapshot(&self) -> OldState {
+        OldState {
+            score: 1.5,
+            cars: vec![],
+        }
+    }
+
+    fn restore(&mut self, _engine: &mut Engine, _state: OldState) {}
+}
+
+impl Snapshot for OldRun {
+    type State = OldState;
+    const NAME: &'static str = "run";
+    const VERSION: u32 = 2;
+
+    fn snapshot(&self) -> OldState {
+        OldState {
+            score: 1.5,
+            cars: vec![Vec2 { x: 100.0, y: 600.0 }],
+        }
+    }
+
+    fn restore(&mut self, _engine: &mut Engine, _state: OldState) {}
+}
+
+#[test]
+fn restored_run_continues_the_same() {
+    let mut run = Run::new();
+    for _ in 0..100 {
+        run.update();
+    }
+    let json = snapshot::to_json(&run).unwrap();
+    let played: Vec<_> = (0..200).map(|_| run.update()).collect();
+    assert!(run.sirens > 0 && run.score > 0);
+
+    let mut restored = snapshot::parse::<Run>(&json).unwrap();
+    assert_eq!(snapshot::to_json(&restored).unwrap(), json);
+    let replayed: Vec<_> = (0..200).map(|_| restored.update()).collect();
+    assert_eq!(replayed, played);
+    assert_eq!((restored.score, restored.sirens), (run.score, run.sirens));
+}
+
+#[test]
+fn rng_sequence_survives_a_snapshot() {
+    let mut rng = GameRng::new(7);
+    for _ in 0..13 {
+        rng.next_u64();
+    }
+    let json = serde_json::to_string(&rng).unwrap();
+    let mut restored: GameRng = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.seed(), 7);
+    for _ in 0..50 {
+        assert_eq!(restored.next_u32(), rng.next_u32());
+    }
+}
+
+#[test]
+fn snapshot_from_another_game_is_rejected() {
+    let json = snapshot::to_json(&OtherGame).unwrap();
+    match snapshot::parse::<Run>(&json) {
+        Err(SnapshotError::WrongGame(game)) => assert_eq!(game, "other"),
+        other => panic!("expected WrongGame, got {other:?}"),
+    }
+}
+
+#[test]
+fn snapshot_from_another_version_is_rejected() {
+    let json = snapshot::to_json(&OldRun).unwrap();
+    match snapshot::parse::<Run>(&json) {
+        Err(SnapshotError::WrongVersion(version)) => assert_eq!(version, 2),
+        other => panic!("expected WrongVersion, got {other:?}"),
+    }
+}
+
+#[test]
+fn same_version_with_wrong_shape_is_invalid() {
+    let json = snapshot::to_json(&OldRun).unwrap().replace("\"version\": 2", "\"version\": 1");
+    assert!(matches!(
+        snapshot::parse::<Run>(&json),
+        Err(SnapshotError::Json(_))
+    ));
+}
+
+#[test]
+fn broken_snapshot_is_rejected() {
+    assert!(matches!(
+        snapshot::parse::<Run>("{\"game\": \"run\""),
+        Err(SnapshotError::Json(_))
+    ));
+}
@@ -0,0 +1,90 @@
+use engine::ecs::{Entity, World};
+use engine::geom::Vec2;
+use engine::rng::GameRng;
+use engine::spawner::{Placement, SpawnRule, Spawner};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Kind {
+    Car,
+    Block,
+}
+
+fn spawn(world: &mut World<Kind>, kind: Kind, pos: Vec2) -> Entity {
+    world.spawn(kind, pos)
+}
+
+fn lanes() -> Placement {
+    Placement::Lanes(vec![100.0, 200.0, 300.0])
+}
+
+#[test]
+fn blocked_lanes_defer_the_spawn() {
+    let mut world = World::default();
+    // something sits in every lane where cars appear
+    for x in [100.0, 200.0, 300.0] {
+        world.spawn(Kind::Block, Vec2 { x, y: 600.0 });
+    }
+    let mut spawner = Spawner::default();
+    spawner.add(
+        SpawnRule::new(vec![(Kind::Car, 1.0)], lanes(), 600.0)
+            .interval(0..0)
+            .min_spacing(50.0),
+    );
+    let mut rng = GameRng::new(1);
+    // returns rather than retrying forever
+    for _ in 0..10 {
+        spawner.update(&mut world, &mut rng, spawn);
+    }
+    assert_eq!(world.count(|k| *k == Kind::Car), 0);
+    assert_eq!(spawner.rules[0].live_count(&world), 0);
+
+    // once the way is clear the deferred spawn goes ahead on the next tick
+    world.positions.iter_mut().for_each(|(_, pos)| pos.y = 0.0);
+    spawner.update(&mut world, &mut rng, spawn);
+    assert_eq!(world.count(|k| *k == Kind::Car), 1);
+}
+
+#[test]
+fn max_live_caps_spawns() {
+    let mut world = World::default();
+    let mut spawner = Spawner::default();
+    spawner.add(
+        SpawnRule::new(vec![(Kind::Car, 1.0)], lanes(), 600.0)
+            .interval(0..0)
+            .max_live(2),
+    );
+    let mut rng = GameRng::new(2);
+    for _ in 0..20 {
+        spawner.update(&mut world, &mut rng, spawn);
+    }
+    assert_eq!(spawner.rules[0].live_count(&world), 2);
+    assert_eq!(world.len(), 2);
+
+    // a despawned car makes room for one more
+    let (car, _) = world.query(|k| *k == Kind::Car).next().unwrap();
+    world.despawn(car);
+    world.flush();
+    spawner.update(&mut world, &mut rng, spawn);
+    assert_eq!(spawner.rules[0].live_count(&world), 2);
+}
+
+#[test]
+fn full_lanes_stop_spawning_under_max_live() {
+    let mut world = World::default();
+    let mut spawner = Spawner::default();
+    spawner.add(
+        SpawnRule::new(vec![(Kind::Car, 1.0)], lanes(), 600.0)
+            .interval(0..0)
+            .min_spacing(50.0)
+            .max_live(5),
+    );
+    let mut rng = GameRng::new(3);
+    // cars never move, so each lane fills once and then blocks
+    for _ in 0..50 {
+        spawner.update(&mut world, &mut rng, spawn);
+    }
+    assert_eq!(spawner.rules[0].live_count(&world), 3);
+    let mut xs: Vec<f32> = world.positions.iter().map(|(_, pos)| pos.x).collect();
+    xs.sort_by(f32::total_cmp);
+    assert_eq!(xs, [100.0, 200.0, 300.0]);
+}
@@ -0,0 +1,133 @@
+use engine::geom::{Rect, Vec2};
+use engine::text::{Align, Font, TextStyle};
+use engine::{SheetRegion, Transform, Zeroable};
+
+// digits only, 8px glyphs four to a row on a 32x24 sheet region
+fn digits() -> Font {
+    Font::with_sheet_region('0'..='9', SheetRegion::new(0, 0, 0, 0, 32, 24), 4)
+}
+
+fn sprites(n: usize) -> (Vec<Transform>, Vec<SheetRegion>) {
+    (vec![Transform::zeroed(); n], vec![SheetRegion::zeroed(); n])
+}
+
+#[test]
+fn wraps_at_word_boundaries() {
+    let font = digits();
+    assert_eq!(font.wrap("12 34 567 8", 60.0, 10.0), ["12 34", "567 8"]);
+    // runs of spaces collapse and each paragraph starts its own line
+    assert_eq!(font.wrap("1  2\n\n3", 60.0, 10.0), ["1 2", "", "3"]);
+}
+
+#[test]
+fn splits_words_too_long_for_a_line() {
+    let font = digits();
+    assert_eq!(
+        font.wrap("1 23456789 0", 40.0, 10.0),
+        ["1", "2345", "6789", "0"]
+    );
+    // narrower than one glyph still fits a glyph per line
+    assert_eq!(font.wrap("12", 4.0, 10.0), ["1", "2"]);
+}
+
+#[test]
+fn measures_the_widest_line() {
+    let font = digits();
+    let style = TextStyle::sized(10.0);
+    assert_eq!(font.measure("", &style), Vec2 { x: 0.0, y: 10.0 });
+    assert_eq!(font.measure("123", &style), Vec2 { x: 30.0, y: 10.0 });
+    // each line after the first adds the line spacing
+    assert_eq!(
+        font.measure("1\n12345\n12", &style),
+        Vec2 {
+            x: 50.0,
+            y: 10.0 + 2.0 * 12.5
+        }
+    );
+}
+
+#[test]
+fn alignment_moves_lines_relative_to_pos() {
+    let font = digits();
+    let (mut transforms, mut uvs) = sprites(8);
+    let pos = Vec2 { x: 100.0, y: 50.0 };
+    for (align, first_x) in [
+        (Align::Left, 105.0),
+        (Align::Center, 90.0),
+        (Align::Right, 75.0),
+    ] {
+        let style = TextStyle::sized(10.0).align(align);
+        assert_eq!(font.draw(&mut transforms, &mut uvs, "123", pos, &style), 3);
+        assert_eq!((transforms[0].x, transforms[0].y), (first_x, 45.0));
+        assert_eq!(transforms[2].x, first_x + 20.0);
+    }
+
+    // every line is aligned on its own
+    let style = TextStyle::sized(10.0).align(Align::Right);
+    assert_eq!(
+        font.draw(&mut transforms, &mut uvs, "12\n3", pos, &style),
+        3
+    );
+    assert_eq!((transforms[2].x, transforms[2].y), (95.0, 32.5));
+}
+
+#[test]
+fn draws_missing_characters_as_question_marks_or_nothing() {
+    let (mut transforms, mut uvs) = sprites(8);
+    let style = TextStyle::sized(8.0);
+
+    // the digits font has no '?', so other characters are skipped
+    let font = digits();
+    assert_eq!(font.sprite_count("1a 2é"), 2);
+    assert_eq!(
+        font.draw(&mut transforms, &mut uvs, "1a2", Vec2::ZERO, &style),
+        2
+    );
+    // but still take up their space
+    assert_eq!(transforms[1].x, 20.0);
+    assert_eq!((uvs[1].x, uvs[1].y), (16, 0));
+
+    let font = Font::ascii(SheetRegion::new(0, 0, 0, 0, 128, 48));
+    assert_eq!(font.sprite_count("aé"), 2);
+    font.draw(&mut transforms, &mut uvs, "é", Vec2::ZERO, &style);
+    let question = font.glyph('?', 0).unwrap();
+    assert_eq!((uvs[0].x, uvs[0].y), (question.x, question.y));
+}
+
+#[test]
+fn box_wraps_aligns_and_drops_overflow() {
+    let font = digits();
+    let (mut transforms, mut uvs) = sprites(16);
+    let area = Rect {
+        corner: Vec2 { x: 0.0, y: 0.0 },
+        size: Vec2 { x: 40.0, y: 25.0 },
+    };
+    // three lines of text, but only two fit in 25 units
+    let style = TextStyle::sized(10.0).align(Align::Center);
+    let drawn = font.draw_in_box(&mut transforms, &mut uvs, "12 34 56", area, &style);
+    assert_eq!(drawn, 4);
+    // lines start at the top of the box, centered in it
+    assert_eq!((transforms[0].x, transforms[0].y), (15.0, 20.0));
+    assert_eq!((transforms[2].x, transforms[2].y), (15.0, 7.5));
+
+    // a box shorter than one line holds nothing
+    let flat = Rect {
+        size: Vec2 { x: 40.0, y: 5.0 },
+        ..area
+    };
+    assert_eq!(
+        font.draw_in_box(&mut transforms, &mut uvs, "1", flat, &style),
+        0
+    );
+}
+
+#[test]
+fn drawing_stops_when_the_sprites_run_out() {
+    let font = digits();
+    let (mut transforms, mut uvs) = sprites(2);
+    let style = TextStyle::default();
+    assert_eq!(
+        font.draw(&mut transforms, &mut uvs, "12345", Vec2::ZERO, &style),
+        2
+    );
+}
@@ -0,0 +1,118 @@
+use engine::geom::Vec2;
+use engine::tiled::{self, TiledError};
+use engine::tilemap::Property;
+use engine::{SheetRegion, Transform, Zeroable};
+
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn loads_tile_layers() {
+    let map = tiled::load(fixture("road.tmj")).unwrap();
+    let tilemap = &map.tilemap;
+    assert_eq!((tilemap.width, tilemap.height), (4, 4));
+    assert_eq!(tilemap.size(), Vec2 { x: 64.0, y: 64.0 });
+
+    let road = tilemap.layer("road").unwrap();
+    assert!(road.visible);
+    assert_eq!(road.tile(0, 0), Some(1));
+    assert_eq!(road.tile(1, 1), None);
+    assert_eq!(road.tile(2, 3), Some(3));
+    assert_eq!(road.tile(4, 0), None);
+
+    // layers inside a hidden group are hidden, and flip flags are stripped
+    let decor = tilemap.layer("decor").unwrap();
+    assert!(!decor.visible);
+    assert_eq!(decor.tile(1, 1), Some(4));
+    assert_eq!(decor.properties["scroll"], Property::Bool(true));
+    assert!(road.depth > decor.depth);
+}
+
+#[test]
+fn maps_tiles_to_sheet_regions() {
+    let map = tiled::load(fixture("road.tmj")).unwrap();
+    let tilemap = &map.tilemap;
+    let first = tilemap.sheet_region(1).unwrap();
+    assert_eq!((first.x, first.y, first.w, first.h), (1, 1, 16, 16));
+    let last = tilemap.sheet_region(4).unwrap();
+    assert_eq!((last.x, last.y), (17, 17));
+    assert!(tilemap.sheet_region(5).is_none());
+
+    assert_eq!(tilemap.sprite_count(), 12);
+    let mut transforms = vec![Transform::zeroed(); 16];
+    let mut uvs = vec![SheetRegion::zeroed(); 16];
+    assert_eq!(tilemap.write_sprites(&mut transforms, &mut uvs), 12);
+    // row 0 is the top of the map
+    assert_eq!((transforms[0].x, transforms[0].y), (8.0, 56.0));
+    assert_eq!((transforms[0].w, transforms[0].h), (16, 16));
+    assert_eq!(uvs[0].depth, tilemap.layer("road").unwrap().depth);
+
+    // writing stops when the output runs out of room
+    assert_eq!(
+        tilemap.write_sprites(&mut transforms[..5], &mut uvs[..5]),
+        5
+    );
+}
+
+#[test]
+fn loads_spawn_objects() {
+    let map = tiled::load(fixture("road.tmj")).unwrap();
+    assert_eq!(map.spawns.len(), 3);
+
+    let player = map.spawn("player").unwrap();
+    assert_eq!(player.kind, "spawn");
+    assert_eq!(player.layer, "spawns");
+    assert_eq!(player.pos, Vec2 { x: 48.0, y: 8.0 });
+
+    let lane = map.spawn("left_lane").unwrap();
+    assert_eq!(lane.kind, "lane");
+    assert_eq!(lane.pos, Vec2 { x: 24.0, y: 32.0 });
+    assert_eq!(lane.size, Vec2 { x: 16.0, y: 64.0 });
+    assert_eq!(lane.properties["speed"], Property::Float(2.5));
+    assert_eq!(lane.properties["max_cars"], Property::Int(3));
+    assert_eq!(lane.properties["label"], Property::String("slow".into()));
+
+    // tile objects are anchored at their bottom-left corner
+    let coin = map.spawn("coin").unwrap();
+    assert_eq!(coin.pos, Vec2 { x: 40.0, y: 8.0 });
+    assert_eq!(map.spawns_of_kind("pickup").count(), 1);
+
+    assert_eq!(
+        map.tilemap.properties["music"],
+        Property::String("../content/coin.mp3".into())
+    );
+}
+
+#[test]
+fn rejects_unsupported_maps() {
+    assert!(matches!(
+        tiled::load(fixture("base64.tmj")),
+        Err(TiledError::UnsupportedEncoding(layer)) if layer == "ground"
+    ));
+    assert!(matches!(
+        tiled::load(fixture("external_tileset.tmj")),
+        Err(TiledError::ExternalTileset(source)) if source == "road.tsj"
+    ));
+    assert!(matches!(
+        tiled::load(fixture("road.tmx")),
+        Err(TiledError::UnsupportedFormat(_))
+    ));
+    assert!(matches!(
+        tiled::load(fixture("missing.tmj")),
+        Err(TiledError::Io(_))
+    ));
+    assert!(matches!(
+        tiled::from_json("{\"width\": 1}"),
+        Err(TiledError::Json(_))
+    ));
+    // a layer taller than the map has rows that fall off the top of it
+    assert!(matches!(
+        tiled::from_json(
+            r#"{"width": 1, "height": 1, "tilewidth": 16, "tileheight": 16, "tilesets": [],
+                "layers": [{"type": "tilelayer", "name": "tall", "width": 1, "height": 2,
+                            "data": [1, 1]}]}"#
+        ),
+        Err(TiledError::BadLayerSize(layer)) if layer == "tall"
+    ));
+}
@@ -0,0 +1,148 @@
+use engine::ease::Ease;
+use engine::geom::{Rect, Vec2};
+use engine::transition::{Overlay, Transition, WipeDir};
+use engine::{SheetRegion, Transform, Zeroable};
+
+const COVER: SheetRegion = SheetRegion::new(0, 0, 0, 0, 1, 1);
+const OVERLAY: Overlay = Overlay {
+    group: 4,
+    cover: COVER,
+};
+
+fn area() -> Rect {
+    Rect {
+        corner: Vec2 { x: 0.0, y: 0.0 },
+        size: Vec2 { x: 400.0, y: 600.0 },
+    }
+}
+
+fn linear(transition: Transition) -> Transition {
+    let mut transition = transition.cells(4, 6);
+    transition.ease = Ease::Linear;
+    transition
+}
+
+fn sprites(transition: &Transition) -> (Vec<Transform>, Vec<SheetRegion>) {
+    let count = transition.sprite_count(area());
+    let mut transforms = vec![Transform::zeroed(); count];
+    let mut uvs = vec![SheetRegion::zeroed(); count];
+    assert_eq!(
+        transition.write_sprites(area(), &mut transforms, &mut uvs),
+        count
+    );
+    (transforms, uvs)
+}
+
+#[test]
+fn fade_covers_then_uncovers_and_switches_once_at_the_middle() {
+    let mut fade = linear(Transition::fade(OVERLAY, 10));
+    assert_eq!(fade.overlay.group, 4);
+    assert_eq!(fade.coverage(), 0.0);
+    let switches: Vec<bool> = (0..12).map(|_| fade.tick()).collect();
+    assert_eq!(
+        switches.iter().position(|s| *s),
+        Some(4),
+        "switch on the fifth tick"
+    );
+    assert_eq!(switches.iter().filter(|s| **s).count(), 1);
+    assert!(fade.is_done());
+    assert_eq!(fade.coverage(), 0.0);
+
+    let mut fade = linear(Transition::fade(OVERLAY, 10));
+    let coverage: Vec<f32> = (0..10)
+        .map(|_| {
+            fade.tick();
+            fade.coverage()
+        })
+        .collect();
+    assert_eq!(coverage[4], 1.0);
+    assert!(coverage[..5].windows(2).all(|w| w[0] < w[1]));
+    assert!(coverage[4..].windows(2).all(|w| w[0] > w[1]));
+}
+
+#[test]
+fn crossfade_starts_covered_and_switches_on_the_first_tick() {
+    let mut crossfade = linear(Transition::crossfade(OVERLAY, 10));
+    assert_eq!(crossfade.coverage(), 1.0);
+    assert!(crossfade.tick());
+    assert!(!crossfade.tick());
+    assert!(crossfade.coverage() < 1.0);
+    while !crossfade.is_done() {
+        crossfade.tick();
+    }
+    assert_eq!(crossfade.coverage(), 0.0);
+    assert_eq!(crossfade.sprite_count(area()), 0);
+}
+
+#[test]
+fn full_fade_tiles_the_area_with_the_cover() {
+    let mut fade = linear(Transition::fade(OVERLAY, 10));
+    for _ in 0..5 {
+        fade.tick();
+    }
+    let (transforms, uvs) = sprites(&fade);
+    assert_eq!(transforms.len(), 4 * 6);
+    assert!(uvs
+        .iter()
+        .all(|uv| (uv.x, uv.y, uv.w, uv.h) == (0, 0, 1, 1)));
+    assert!(transforms.iter().all(|t| (t.w, t.h) == (100, 100)));
+    // first piece is the bottom left cell
+    assert_eq!((transforms[0].x, transforms[0].y), (50.0, 50.0));
+    let last = transforms.last().unwrap();
+    assert_eq!((last.x, last.y), (350.0, 550.0));
+}
+
+#[test]
+fn partial_fade_covers_cells_in_dither_order() {
+    let mut fade = linear(Transition::fade(OVERLAY, 32));
+    let mut last = 0;
+    while !fade.tick() {
+        let count = fade.sprite_count(area());
+        assert!(count >= last);
+        last = count;
+    }
+    assert!(last > 0 && last < 4 * 6);
+}
+
+#[test]
+fn wipe_enters_from_one_edge_and_leaves_through_the_other() {
+    let mut wipe = linear(Transition::wipe(WipeDir::Right, OVERLAY, 8));
+    wipe.tick();
+    wipe.tick();
+    let (transforms, _) = sprites(&wipe);
+    assert_eq!(transforms.len(), 1);
+    // half covered from the left
+    assert_eq!((transforms[0].x, transforms[0].w), (100.0, 200));
+    assert_eq!((transforms[0].y, transforms[0].h), (300.0, 600));
+
+    for _ in 0..4 {
+        wipe.tick();
+    }
+    let (transforms, _) = sprites(&wipe);
+    // half uncovered, still from the left
+    assert_eq!((transforms[0].x, transforms[0].w), (300.0, 200));
+}
+
+#[test]
+fn crossfade_cells_show_their_part_of_the_image() {
+    let image = SheetRegion::new(0, 10, 20, 3, 400, 600);
+    let crossfade = linear(Transition::crossfade(
+        Overlay {
+            group: 1,
+            cover: image,
+        },
+        10,
+    ));
+    let (transforms, uvs) = sprites(&crossfade);
+    assert_eq!(uvs.len(), 4 * 6);
+    // the bottom left cell shows the bottom left of the image, which is the
+    // bottom of the sheet region
+    assert_eq!((transforms[0].x, transforms[0].y), (50.0, 50.0));
+    assert_eq!(
+        (uvs[0].x, uvs[0].y, uvs[0].w, uvs[0].h),
+        (10, 520, 100, 100)
+    );
+    let last = uvs.last().unwrap();
+    assert_eq!((last.x, last.y), (310, 20));
+    assert!(uvs.iter().all(|uv| uv.depth == 3));
+}
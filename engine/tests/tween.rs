@@ -0,0 +1,112 @@
+use engine::geom::Vec2;
+use engine::tween::{Ease, Sequence, Tween};
+
+const EASES: [Ease; 9] = [
+    Ease::Linear,
+    Ease::QuadIn,
+    Ease::QuadOut,
+    Ease::QuadInOut,
+    Ease::CubicIn,
+    Ease::CubicOut,
+    Ease::CubicInOut,
+    Ease::SmoothStep,
+    Ease::Step,
+];
+
+#[test]
+fn eases_start_at_0_end_at_1_and_clamp() {
+    for ease in EASES {
+        assert_eq!(ease.apply(0.0), 0.0, "{ease:?}");
+        assert_eq!(ease.apply(1.0), 1.0, "{ease:?}");
+        assert_eq!(ease.apply(-1.0), 0.0, "{ease:?}");
+        assert_eq!(ease.apply(2.0), 1.0, "{ease:?}");
+    }
+    assert_eq!(Ease::Linear.apply(0.25), 0.25);
+    // ins start slow, outs start fast, in-outs meet in the middle
+    assert!(Ease::QuadIn.apply(0.25) < 0.25);
+    assert!(Ease::CubicIn.apply(0.25) < Ease::QuadIn.apply(0.25));
+    assert!(Ease::QuadOut.apply(0.25) > 0.25);
+    assert_eq!(Ease::QuadInOut.apply(0.5), 0.5);
+    assert_eq!(Ease::SmoothStep.apply(0.5), 0.5);
+    assert_eq!(Ease::Step.apply(0.99), 0.0);
+}
+
+#[test]
+fn tween_runs_from_start_to_end() {
+    let mut tween = Tween::new(10.0, 20.0, 4);
+    assert_eq!(tween.value(), 10.0);
+    assert!(!tween.is_done());
+    assert_eq!(tween.tick(), 12.5);
+    assert_eq!(tween.progress(), 0.25);
+    for _ in 0..3 {
+        tween.tick();
+    }
+    assert!(tween.is_done());
+    assert_eq!(tween.value(), 20.0);
+    // ticking past the end stays there
+    assert_eq!(tween.tick(), 20.0);
+
+    tween.reset();
+    assert_eq!(tween.value(), 10.0);
+}
+
+#[test]
+fn tween_follows_its_ease() {
+    let mut tween = Tween::new(Vec2::ZERO, Vec2 { x: 100.0, y: -50.0 }, 2).eased(Ease::QuadIn);
+    assert_eq!(tween.tick(), Vec2 { x: 25.0, y: -12.5 });
+    assert_eq!(tween.tick(), Vec2 { x: 100.0, y: -50.0 });
+}
+
+#[test]
+fn zero_tick_tween_is_already_done() {
+    let tween = Tween::new(1.0, 3.0, 0);
+    assert!(tween.is_done());
+    assert_eq!(tween.value(), 3.0);
+}
+
+#[test]
+fn tween_reports_completion_once() {
+    let mut tween = Tween::new(0.0, 1.0, 2);
+    let mut completed = Vec::new();
+    for _ in 0..4 {
+        tween.tick_then(|value| completed.push(value));
+    }
+    assert_eq!(completed, [1.0]);
+}
+
+#[test]
+fn sequence_chains_steps_from_where_the_last_ended() {
+    // a jump: up, hang, and back down
+    let mut jump =
+        Sequence::new(0.0)
+            .then(40.0, 2, Ease::Linear)
+            .wait(2)
+            .then(0.0, 4, Ease::Linear);
+    assert_eq!(jump.value(), 0.0);
+    let values: Vec<f32> = (0..8).map(|_| jump.tick()).collect();
+    assert_eq!(values, [20.0, 40.0, 40.0, 40.0, 30.0, 20.0, 10.0, 0.0]);
+    assert!(jump.is_done());
+    assert_eq!(jump.step(), 3);
+    assert_eq!(jump.tick(), 0.0);
+
+    jump.reset();
+    assert_eq!((jump.step(), jump.value()), (0, 0.0));
+    assert_eq!(jump.tick(), 20.0);
+}
+
+#[test]
+fn sequence_reports_completion_once() {
+    let mut slide = Sequence::new(Vec2::ZERO)
+        .then(Vec2 { x: 8.0, y: 0.0 }, 1, Ease::QuadOut)
+        .then(Vec2 { x: 8.0, y: 8.0 }, 1, Ease::QuadOut);
+    let mut completed = Vec::new();
+    for _ in 0..3 {
+        slide.tick_then(|value| completed.push(value));
+    }
+    assert_eq!(completed, [Vec2 { x: 8.0, y: 8.0 }]);
+
+    // an empty sequence holds its start
+    let empty = Sequence::new(5.0);
+    assert!(empty.is_done());
+    assert_eq!(empty.value(), 5.0);
+}
@@ -0,0 +1,134 @@
+use engine::geom::{Rect, Vec2};
+use engine::keys::FrameKeys;
+use engine::sprites::{pause_menu, PauseChoice};
+use engine::text::{Font, TextStyle};
+use engine::ui::{Ui, UiNav, UiSkin};
+use engine::{Key, SheetRegion, Zeroable};
+
+fn ui() -> Ui {
+    let region = SheetRegion::zeroed();
+    Ui::new(UiSkin {
+        font: Font::ascii(region),
+        text: TextStyle::default(),
+        panel: region,
+        button: region,
+        button_focused: region,
+        bar_back: region,
+        bar_fill: region,
+    })
+}
+
+fn area(row: f32) -> Rect {
+    Rect {
+        corner: Vec2 {
+            x: 0.0,
+            y: row * 40.0,
+        },
+        size: Vec2 { x: 100.0, y: 32.0 },
+    }
+}
+
+// one frame of a menu with three buttons; returns which one was activated
+fn menu(ui: &mut Ui, keys: &mut FrameKeys) -> Option<usize> {
+    ui.begin(keys);
+    let activated = (0..3).find(|row| ui.button(area(*row as f32), "go"));
+    keys.next_frame();
+    activated
+}
+
+fn tap(ui: &mut Ui, keys: &mut FrameKeys, key: Key) -> Option<usize> {
+    keys.press(key);
+    let activated = menu(ui, keys);
+    keys.release(key);
+    activated
+}
+
+#[test]
+fn arrows_move_focus_and_wrap() {
+    let (mut ui, mut keys) = (ui(), FrameKeys::default());
+    menu(&mut ui, &mut keys);
+    assert_eq!(ui.focus(), 0);
+    tap(&mut ui, &mut keys, Key::Down);
+    assert_eq!(ui.focus(), 1);
+    tap(&mut ui, &mut keys, Key::S);
+    tap(&mut ui, &mut keys, Key::Down);
+    assert_eq!(ui.focus(), 0);
+    tap(&mut ui, &mut keys, Key::Up);
+    assert_eq!(ui.focus(), 2);
+}
+
+#[test]
+fn held_key_moves_focus_once() {
+    let (mut ui, mut keys) = (ui(), FrameKeys::default());
+    menu(&mut ui, &mut keys);
+    keys.press(Key::Down);
+    // several frames go by before the key is let go, as on a fast display
+    for _ in 0..4 {
+        menu(&mut ui, &mut keys);
+    }
+    assert_eq!(ui.focus(), 1);
+    keys.release(Key::Down);
+    tap(&mut ui, &mut keys, Key::Down);
+    assert_eq!(ui.focus(), 2);
+}
+
+#[test]
+fn activate_reaches_the_focused_button_once() {
+    let (mut ui, mut keys) = (ui(), FrameKeys::default());
+    menu(&mut ui, &mut keys);
+    tap(&mut ui, &mut keys, Key::Down);
+    keys.press(Key::Return);
+    assert_eq!(menu(&mut ui, &mut keys), Some(1));
+    assert_eq!(menu(&mut ui, &mut keys), None);
+    keys.release(Key::Return);
+    assert_eq!(tap(&mut ui, &mut keys, Key::Space), Some(1));
+}
+
+#[test]
+fn queued_navigation_applies_on_next_begin() {
+    let (mut ui, mut keys) = (ui(), FrameKeys::default());
+    menu(&mut ui, &mut keys);
+    ui.navigate(UiNav::Down);
+    ui.navigate(UiNav::Activate);
+    assert_eq!(menu(&mut ui, &mut keys), Some(1));
+}
+
+#[test]
+fn slider_steps_and_clamps() {
+    let (mut ui, mut keys) = (ui(), FrameKeys::default());
+    let mut value = 0.5;
+    for (key, expected, changed) in [
+        (Key::Right, 0.75, true),
+        (Key::D, 1.0, true),
+        (Key::Right, 1.0, false),
+        (Key::Left, 0.75, true),
+    ] {
+        keys.press(key);
+        ui.begin(&keys);
+        assert_eq!(ui.slider(area(0.0), &mut value, 0.0, 1.0, 0.25), changed);
+        assert_eq!(value, expected);
+        keys.next_frame();
+        keys.release(key);
+    }
+}
+
+#[test]
+fn pause_menu_resumes_or_quits() {
+    let (mut ui, mut keys) = (ui(), FrameKeys::default());
+    let mut frame = |ui: &mut Ui, key: Option<Key>| {
+        if let Some(key) = key {
+            keys.press(key);
+        }
+        ui.begin(&keys);
+        let choice = pause_menu(ui, 768.0, 1280.0);
+        keys.next_frame();
+        if let Some(key) = key {
+            keys.release(key);
+        }
+        choice
+    };
+    assert_eq!(frame(&mut ui, None), None);
+    assert_eq!(frame(&mut ui, Some(Key::Return)), Some(PauseChoice::Resume));
+    frame(&mut ui, Some(Key::Down));
+    assert_eq!(frame(&mut ui, Some(Key::Return)), Some(PauseChoice::Quit));
+}
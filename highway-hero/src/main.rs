@@ -1,27 +1,146 @@
 use engine;
-use engine::wgpu;
+use engine::audio::Audio;
+use engine::camera::CameraController;
+use engine::config::{self, Config, Problems};
+use engine::debug::DebugOverlay;
+use engine::devtext::DevText;
+use engine::difficulty::{Curve, Difficulty, Measure};
+use engine::ease::Ease;
+use engine::ecs::{Collider, Entity, Visual, World};
+use engine::events::EventBus;
+use engine::hud::{Anchor, Hud, HudElement, HudId};
+use engine::particles::{Emitter, Particles};
+use engine::pointer::SwipeDir;
+use engine::rng::GameRng;
+use engine::save::{player_name, HighScores, ScoreEntry};
+use engine::scheduler::Scheduler;
+use engine::snapshot::{self, Snapshot};
+use engine::spawner::{Placement, SpawnRule, Spawner};
+use engine::text::TextStyle;
+use engine::transition::{self, Overlay, Transition, TransitionKind};
+use engine::tween::{Sequence, Tween};
+use engine::ui::{Ui, UiSkin};
 use engine::{geom::*, sprites::*, Camera, Engine, SheetRegion, Transform, Zeroable};
-use rand::{distributions::Uniform, Rng};
+use serde::{Deserialize, Serialize};
 const W: f32 = 768.0;
 const H: f32 = 1280.0;
-const PAVEMENT_SPEED: f32 = -1.0;
 const SPRITE_MAX: usize = 1000;
-const COLLISION_DISTANCE: f32 = 22.0;
-use kira::{
-    manager::{backend::DefaultBackend, AudioManager, AudioManagerSettings},
-    sound::static_sound::{StaticSoundData, StaticSoundSettings},
-};
-const COP_DISTANCE: f32 = 42.0;
+use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
+use std::ops::Range;
 const COLLISION_STEPS: usize = 3;
 const GUY_Y_POS: f32 = 24.0;
+// where a jump lands
+const JUMP_LANDING_Y: f32 = 50.0;
+// the tuning file in the content folder
+const TUNING_FILE: &str = "highway-hero.toml";
+
+// values for balancing the game, read from `TUNING_FILE`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Tuning {
+    // how far the pavements scroll each tick
+    pavement_speed: f32,
+    // a car hits the guy when their centers are this close
+    collision_distance: f32,
+    // and a car this close brings the cop
+    cop_distance: f32,
+    // how far a jump rises
+    jump_height: f32,
+    // x positions of the road lanes, left to right
+    lanes: Vec<f32>,
+    // ticks between cars and between coins, chosen uniformly
+    car_interval: Range<u32>,
+    coin_interval: Range<u32>,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Tuning {
+            pavement_speed: -1.0,
+            collision_distance: 22.0,
+            cop_distance: 42.0,
+            jump_height: 106.8,
+            lanes: vec![261.33, 378.66, 496.0],
+            car_interval: 30..90,
+            coin_interval: 30..90,
+        }
+    }
+}
+
+impl Config for Tuning {
+    fn validate(&self, problems: &mut Problems) {
+        problems.positive("collision_distance", self.collision_distance);
+        problems.at_least("cop_distance", self.cop_distance, self.collision_distance);
+        problems.positive("jump_height", self.jump_height);
+        problems.not_empty("lanes", &self.lanes);
+        problems.ticks("car_interval", &self.car_interval);
+        problems.ticks("coin_interval", &self.coin_interval);
+    }
+}
+
+impl Tuning {
+    // cars and coins drop into a random lane
+    fn spawn_rule(&self, thing: Thing) -> SpawnRule<Thing> {
+        let interval = match thing {
+            Thing::Car => self.car_interval.clone(),
+            Thing::Coin => self.coin_interval.clone(),
+        };
+        SpawnRule::new(vec![(thing, 1.0)], Placement::Lanes(self.lanes.clone()), H + 8.0)
+            .interval(interval)
+            .min_spacing(self.collision_distance)
+            .max_live(32)
+    }
+}
+#[derive(Clone, Serialize, Deserialize)]
 struct Guy {
     pos: Vec2,
     is_jumping: bool,
-    jump_velocity: f32,
-    fwd_jump_frames: usize,
+    // vertical motion in progress: the guy's jump or the cop sliding away
+    y_motion: Option<Sequence<f32>>,
     is_visible: bool,
 }
 
+// Cop timers only run while the guy is jumping, so the cop gives up on a guy
+// who keeps jumping over cars and catches one who doesn't.
+#[derive(Clone, Serialize, Deserialize)]
+enum CopTimer {
+    // the cop can make an arrest
+    Grace,
+    // the cop slides off the screen
+    Leave,
+}
+
+// what happens during a run, for sound, effects and scoring to react to
+#[derive(Clone, Copy, Debug)]
+enum GameEvent {
+    CoinCollected { pos: Vec2 },
+    Crashed { pos: Vec2 },
+}
+
+// the kinds of entity that scroll down the road
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+enum Thing {
+    Car,
+    Coin,
+}
+
+impl Thing {
+    fn spawn(self, world: &mut World<Thing>, pos: Vec2, radius: f32) -> Entity {
+        let (size, uv) = match self {
+            Thing::Car => (Vec2 { x: 38.4, y: 65.33 }, SheetRegion::new(0, 27, 525, 3, 27, 32)),
+            Thing::Coin => (Vec2 { x: 33.0, y: 38.0 }, SheetRegion::new(0, 20, 480, 2, 16, 16)),
+        };
+        let entity = world.spawn(self, pos);
+        world.velocities.insert(entity, Vec2 { x: 0.0, y: -2.0 });
+        world.visuals.insert(entity, Visual { size, uv });
+        world.colliders.insert(
+            entity,
+            Collider { radius },
+        );
+        entity
+    }
+}
+
 enum GameState {
     TitleScreen,
     InGame,
@@ -29,72 +148,280 @@ enum GameState {
 }
 
 struct Game {
-    camera: engine::Camera,
+    tuning: Tuning,
+    // follows the guy and shakes on crashes
+    camera: CameraController,
     walls: Vec<SPRITE>,
     guy: Guy,
     cop: Guy,
-    cars: Vec<Sprite>,
-    car_timer: u32,
-    car_speed_multiplier: f32,
-    coin_speed_multiplier: f32,
-    coins: Vec<Sprite>,
-    coin_timer: u32,
+    // the cop's grace period and exit, counted in jumping ticks
+    cop_timers: Scheduler<CopTimer>,
+    cop_can_catch: bool,
+    cop_leaving: bool,
+    // time scale easing back to normal after a near miss
+    slow_motion: Option<Tween<f32>>,
+    things: World<Thing>,
+    spawner: Spawner<Thing>,
+    // index of the car rule in `spawner`
+    car_rule: usize,
+    difficulty: Difficulty,
+    // scroll speed multiplier for cars and coins
+    speed_curve: Curve,
+    // car spawn rate multiplier
+    car_rate_curve: Curve,
     pavements: Vec<Sprite>,
     score: u32,
-    font: engine::BitFont,
-    font_end: engine::BitFont,
+    hud: Hud,
+    score_text: HudId,
+    level_text: HudId,
+    font_end: engine::text::Font,
     curr_frame: usize,
     frame_counter: usize,
     frame_direction: isize,
     game_over: bool,
     game_state: GameState,
+    start_group: usize,
+    end_group: usize,
+    // menus and the game over summary
+    ui: Ui,
+    ui_group: usize,
+    // the screen transition in progress, and the state to switch to once it
+    // covers the screen
+    transition: Option<Transition>,
+    next_state: Option<GameState>,
+    // solid black, for fading out of gameplay
+    fade: Overlay,
+    particles: Particles,
+    // colliders, hitboxes and stats, shown with the engine's debug key
+    debug: DebugOverlay,
+    // the console and the log view, drawn over everything in a group of their own
+    dev_text: DevText,
+    // emitter indices in `particles`
+    coin_sparks: usize,
+    crash_sparks: usize,
+    events: EventBus<GameEvent, Game>,
+    rng: GameRng,
+    high_scores: HighScores,
     // coin sound
-    audio_manager: AudioManager<DefaultBackend>,
+    audio: Audio,
     coin_sound: StaticSoundData,
 }
 
+impl Game {
+    // the camera the current scene is drawn with
+    fn active_camera(&self) -> engine::Camera {
+        match self.game_state {
+            GameState::InGame => self.camera.camera(),
+            _ => self.camera.screen_camera(),
+        }
+    }
+
+    fn keep_score(&mut self, event: &GameEvent) {
+        if let GameEvent::CoinCollected { .. } = event {
+            self.score += 1;
+        }
+    }
+
+    fn play_sounds(&mut self, event: &GameEvent) {
+        if let GameEvent::CoinCollected { .. } = event {
+            self.audio.play(&self.coin_sound);
+        }
+    }
+
+    fn show_effects(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::CoinCollected { pos } => self.particles.burst(self.coin_sparks, pos, 8),
+            GameEvent::Crashed { pos } => {
+                self.camera.add_trauma(0.8);
+                self.particles.burst(self.crash_sparks, pos, 24);
+            }
+        }
+    }
+
+    // close in on the crash, then open on the end screen
+    fn end_run(&mut self, event: &GameEvent) {
+        if let GameEvent::Crashed { .. } = event {
+            if self.transition.is_none() {
+                self.transition = Some(Transition::iris(self.fade, 60));
+                self.next_state = Some(GameState::GameOver);
+                self.record_score();
+            }
+        }
+    }
+
+    fn print_stats(&mut self, event: &GameEvent) {
+        if let GameEvent::Crashed { .. } = event {
+            log::info!(target: "score", "final score: {}", self.score);
+        }
+    }
+
+    // loads the tuning file again if it changed on disk, keeping the current
+    // values if the new ones can't be used
+    fn reload_tuning(&mut self, engine: &mut Engine) {
+        let changed = engine.assets.take_changed();
+        if !changed.iter().any(|path| path.as_os_str() == TUNING_FILE) {
+            return;
+        }
+        match config::load::<Tuning>(&engine.assets, TUNING_FILE) {
+            Ok(tuning) => {
+                log::info!(target: "config", "reloaded {TUNING_FILE}");
+                for rule in self.spawner.rules.iter_mut() {
+                    let Some((thing, _)) = rule.kinds.first() else {
+                        continue;
+                    };
+                    let new_rule = tuning.spawn_rule(*thing);
+                    rule.placement = new_rule.placement;
+                    rule.interval = new_rule.interval;
+                    rule.min_spacing = new_rule.min_spacing;
+                }
+                self.tuning = tuning;
+            }
+            Err(err) => log::error!(target: "config", "{TUNING_FILE}: {err}"),
+        }
+    }
+
+    // runs the commands typed into the developer console
+    fn run_commands(&mut self, engine: &mut Engine) {
+        for command in engine.console.take_commands() {
+            let arg = command.args.first().map(String::as_str);
+            let pos = Vec2 {
+                x: self.guy.pos.x,
+                y: H + 8.0,
+            };
+            match (command.name.as_str(), arg) {
+                ("score", Some(score)) => match score.parse() {
+                    Ok(score) => self.score = score,
+                    Err(_) => engine.console.print("score takes a whole number"),
+                },
+                ("spawn", Some("car")) => {
+                    Thing::Car.spawn(&mut self.things, pos, self.tuning.collision_distance);
+                }
+                ("spawn", Some("coin")) => {
+                    Thing::Coin.spawn(&mut self.things, pos, self.tuning.collision_distance);
+                }
+                ("score", _) => engine.console.print("score <n>"),
+                _ => engine.console.print("spawn car|coin"),
+            }
+        }
+    }
+
+    // add the final score to the high score table and save it
+    fn record_score(&mut self) {
+        let entry = ScoreEntry::new(&player_name(), self.score, self.rng.seed());
+        if self.high_scores.submit(entry).is_some() {
+            if let Err(err) = self.high_scores.save() {
+                log::error!(target: "save", "could not save high scores: {err}");
+            }
+        }
+    }
+}
+
+// everything that changes during a run, for quick save (F5) and load (F9)
+#[derive(Serialize, Deserialize)]
+struct SavedRun {
+    guy: Guy,
+    cop: Guy,
+    cop_timers: Scheduler<CopTimer>,
+    cop_can_catch: bool,
+    cop_leaving: bool,
+    things: World<Thing>,
+    spawner: Spawner<Thing>,
+    difficulty: Difficulty,
+    pavements: Vec<Sprite>,
+    score: u32,
+    curr_frame: usize,
+    frame_counter: usize,
+    frame_direction: isize,
+    rng: GameRng,
+}
+
+impl Snapshot for Game {
+    type State = SavedRun;
+    const NAME: &'static str = "highway-hero";
+
+    fn snapshot(&self) -> SavedRun {
+        SavedRun {
+            guy: self.guy.clone(),
+            cop: self.cop.clone(),
+            cop_timers: self.cop_timers.clone(),
+            cop_can_catch: self.cop_can_catch,
+            cop_leaving: self.cop_leaving,
+            things: self.things.clone(),
+            spawner: self.spawner.clone(),
+            difficulty: self.difficulty.clone(),
+            pavements: self.pavements.clone(),
+            score: self.score,
+            curr_frame: self.curr_frame,
+            frame_counter: self.frame_counter,
+            frame_direction: self.frame_direction,
+            rng: self.rng.clone(),
+        }
+    }
+
+    fn restore(&mut self, engine: &mut Engine, state: SavedRun) {
+        self.guy = state.guy;
+        self.cop = state.cop;
+        self.cop_timers = state.cop_timers;
+        self.cop_can_catch = state.cop_can_catch;
+        self.cop_leaving = state.cop_leaving;
+        self.things = state.things;
+        self.spawner = state.spawner;
+        self.difficulty = state.difficulty;
+        self.pavements = state.pavements;
+        self.score = state.score;
+        self.curr_frame = state.curr_frame;
+        self.frame_counter = state.frame_counter;
+        self.frame_direction = state.frame_direction;
+        self.rng = state.rng;
+        self.particles.clear();
+        self.events.clear();
+        self.slow_motion = None;
+        engine.set_time_scale(1.0);
+    }
+}
+
 impl engine::Game for Game {
     // create new game instance
     fn new(engine: &mut Engine) -> Self {
+        let tuning: Tuning = config::load_or_default(&engine.assets, TUNING_FILE);
+
+        // knobs for tuning from the developer console
+        let console = &mut engine.console;
+        console.cvar("car_speed", 1.0f32, "how fast cars drive, on top of the road's speed");
+        console.cvar("spawn_rate", 1.0f32, "multiplies how often cars and coins appear");
+        console.cvar("god_mode", false, "cars drive through the guy");
+        console.command("score", "score <n> sets the score");
+        console.command("spawn", "spawn car|coin drops one into the guy's lane");
+
         let camera = Camera {
             screen_pos: [0.0, 0.0],
             screen_size: [W, H],
         };
 
         #[cfg(not(target_arch = "wasm32"))]
-        let start_img = image::open("../content/title_screen2.png")
-            .unwrap()
-            .into_rgba8();
-        let start_tex = engine.renderer.gpu.create_texture(
-            &start_img,
-            wgpu::TextureFormat::Rgba8UnormSrgb,
-            start_img.dimensions(),
-            Some("start-sprite.png"),
-        );
-        let end_img = image::open("../content/end_screen.png")
-            .unwrap()
-            .into_rgba8();
-        let end_tex = engine.renderer.gpu.create_texture(
-            &end_img,
-            wgpu::TextureFormat::Rgba8UnormSrgb,
-            end_img.dimensions(),
-            Some("end-sprite.png"),
-        );
+        let start_tex = engine
+            .assets
+            .load_texture(&engine.renderer.gpu, "title_screen2.png")
+            .unwrap();
+        let end_tex = engine
+            .assets
+            .load_texture(&engine.renderer.gpu, "end_screen.png")
+            .unwrap();
 
-        let sprite_img = image::open("../content/spritesheet.png")
-            .unwrap()
-            .into_rgba8();
-        let sprite_tex = engine.renderer.gpu.create_texture(
-            &sprite_img,
-            wgpu::TextureFormat::Rgba8UnormSrgb,
-            sprite_img.dimensions(),
-            Some("spr-demo.png"),
-        );
+        let ui_tex = engine
+            .assets
+            .load_texture(&engine.renderer.gpu, "ascii.png")
+            .unwrap();
+
+        let sprite_tex = engine
+            .assets
+            .load_texture(&engine.renderer.gpu, "spritesheet.png")
+            .unwrap();
 
         // game sprite group
         engine.renderer.sprites.add_sprite_group(
             &engine.renderer.gpu,
-            &sprite_tex,
+            engine.assets.texture(sprite_tex),
             // &title_screen_tex,
             vec![Transform::zeroed(); SPRITE_MAX], //bg, three walls, guy, a few cars
             vec![SheetRegion::zeroed(); SPRITE_MAX],
@@ -102,31 +429,44 @@ impl engine::Game for Game {
         );
 
         // start sprite group
-        engine.renderer.sprites.add_sprite_group(
+        let start_group = engine.renderer.sprites.add_sprite_group(
             &engine.renderer.gpu,
-            &start_tex,
+            engine.assets.texture(start_tex),
             vec![Transform::zeroed(); 1], //bg, three walls, guy, a few cars
             vec![SheetRegion::zeroed(); 1],
             camera,
         );
 
         // end sprite group
-        engine.renderer.sprites.add_sprite_group(
+        let end_group = engine.renderer.sprites.add_sprite_group(
             &engine.renderer.gpu,
-            &end_tex,
+            engine.assets.texture(end_tex),
             vec![Transform::zeroed(); 1], //bg, three walls, guy, a few cars
             vec![SheetRegion::zeroed(); 1],
             camera,
         );
 
+        // ui sprite group, resized each frame to fit the widgets
+        let ui_group = engine.renderer.sprites.add_sprite_group(
+            &engine.renderer.gpu,
+            engine.assets.texture(ui_tex),
+            vec![Transform::zeroed(); 1],
+            vec![SheetRegion::zeroed(); 1],
+            camera,
+        );
+
+        // transition overlay group, last so it draws over everything
+        let fade = transition::add_color_group(&mut engine.renderer, [0, 0, 0, 255], camera);
+        // the console's text goes over even the transitions
+        let dev_text = DevText::new(engine, ui_tex, UiSkin::ascii().font, camera);
+
         let guy = Guy {
             pos: Vec2 {
                 x: 378.66,
                 y: GUY_Y_POS,
             },
             is_jumping: false,
-            jump_velocity: 10.0,
-            fwd_jump_frames: 0,
+            y_motion: None,
             is_visible: true,
         };
         let cop = Guy {
@@ -135,8 +475,7 @@ impl engine::Game for Game {
                 y: -50.0,
             },
             is_jumping: false,
-            jump_velocity: 10.0,
-            fwd_jump_frames: 0,
+            y_motion: None,
             is_visible: false,
         };
 
@@ -158,17 +497,36 @@ impl engine::Game for Game {
             size: Vec2 { x: 288.0, y: H },
         };
 
-        let font = engine::BitFont::with_sheet_region(
+        let font = engine::text::Font::with_sheet_region(
             '0'..='9',
             SheetRegion::new(0, 0, 512, 0, 80, 8),
             10,
         );
-        let font_end = engine::BitFont::with_sheet_region(
+        let font_end = engine::text::Font::with_sheet_region(
             '0'..='9',
             SheetRegion::new(0, 0, 868, 0, 80, 8),
             10,
         );
 
+        let mut hud = Hud::new(font.clone());
+        // outlines in the yellow of the lane paint
+        let debug = DebugOverlay::new(font, SheetRegion::new(0, 258, 100, 0, 4, 4));
+        let score_text = hud.add(HudElement::text(
+            Anchor::TopLeft,
+            Vec2 { x: 16.0, y: -16.0 },
+            "0",
+            TextStyle::sized(16.0),
+        ));
+        let level_text = hud.add(
+            HudElement::text(
+                Anchor::Top,
+                Vec2 { x: 0.0, y: -16.0 },
+                "1",
+                TextStyle::sized(16.0),
+            )
+            .pivot(Anchor::Top),
+        );
+
         let mut pavements = Vec::with_capacity(34);
         // right pavement
         pavements.push(Sprite {
@@ -180,40 +538,104 @@ impl engine::Game for Game {
             pos: Vec2 { x: 2.0, y: 0.0 },
             vel: Vec2 { x: 0.0, y: -1.0 },
         });
-        let car_speed_multiplier = 1.0;
-        let coin_speed_multiplier = 1.0;
+        let mut spawner = Spawner::default();
+        let car_rule = spawner.add(tuning.spawn_rule(Thing::Car));
+        spawner.add(tuning.spawn_rule(Thing::Coin));
+
+        // a new level every 30 seconds; the road speeds up and fills with cars
+        // over the first few minutes
+        let difficulty = Difficulty::new(Measure::Time, 30.0, 9);
+        let speed_curve = Curve::ramp(1.0, 2.0, 240.0).eased(Ease::SmoothStep);
+        let car_rate_curve = Curve::new(vec![(0.0, 1.0), (60.0, 1.2), (180.0, 1.8)]);
+
+        // sparks are cut from the yellow lane paint, cooling to the grey of the walls
+        let rng = GameRng::from_entropy();
+        let mut particles = Particles::new(rng.seed());
+        let spark_frames = vec![
+            SheetRegion::new(0, 258, 100, 0, 4, 4),
+            SheetRegion::new(0, 0, 480, 0, 8, 8),
+        ];
+        let coin_sparks = particles.add(
+            Emitter::new(spark_frames.clone())
+                .lifetime(15..30)
+                .speed(80.0..160.0)
+                .size(Vec2::splat(8.0), Vec2::splat(2.0), Ease::QuadIn),
+        );
+        let crash_sparks = particles.add(
+            Emitter::new(spark_frames)
+                .lifetime(30..60)
+                .speed(120.0..320.0)
+                .gravity(Vec2 { x: 0.0, y: -400.0 })
+                .size(Vec2::splat(14.0), Vec2::splat(4.0), Ease::QuadOut),
+        );
+
+        // subscribers run in this order for each event
+        let mut events = EventBus::default();
+        events.subscribe(Game::keep_score);
+        events.subscribe(Game::play_sounds);
+        events.subscribe(Game::show_effects);
+        events.subscribe(Game::end_run);
+        events.subscribe(Game::print_stats);
 
         // coin sound
-        // Create an audio manager
-        let audio_manager =
-            AudioManager::<DefaultBackend>::new(AudioManagerSettings::default()).unwrap();
+        // Open the audio device; pausing the game pauses its sound
+        let audio = Audio::open();
         // Load the coin sound
         let coin_sound =
             StaticSoundData::from_file("../content/coin.mp3", StaticSoundSettings::default())
                 .unwrap();
 
+        // the road fills the screen, so the view only moves when it shakes
+        let world = Rect {
+            corner: Vec2::ZERO,
+            size: Vec2 { x: W, y: H },
+        };
+        let camera = CameraController::new(world.size).bounds(world).smoothing(0.1);
+
         Game {
+            tuning,
             camera,
             guy,
             cop,
+            cop_timers: Scheduler::default(),
+            cop_can_catch: false,
+            cop_leaving: false,
+            slow_motion: None,
             walls: vec![left_wall, right_wall, floor],
-            cars: Vec::with_capacity(8),
-            car_timer: 0,
-            coins: Vec::with_capacity(33),
-            coin_timer: 0,
-            car_speed_multiplier,
-            coin_speed_multiplier,
+            things: World::default(),
+            spawner,
+            car_rule,
+            difficulty,
+            speed_curve,
+            car_rate_curve,
             pavements,
             score: 0,
-            font,
+            hud,
+            score_text,
+            level_text,
             font_end,
             curr_frame: 0,
             frame_counter: 0,
             frame_direction: 1,
             game_over: false,
             game_state: GameState::TitleScreen,
+            start_group,
+            end_group,
+            ui: Ui::new(UiSkin::ascii()),
+            ui_group,
+            transition: None,
+            next_state: None,
+            fade,
+            particles,
+            debug,
+            dev_text,
+            coin_sparks,
+            crash_sparks,
+            events,
+            rng,
+            high_scores: HighScores::load(<Self as Snapshot>::NAME),
             // coin sound
-            audio_manager,
+            audio,
             coin_sound,
         }
     }
@@ -222,18 +644,77 @@ impl engine::Game for Game {
         self.game_over
     }
 
-    fn update(&mut self, engine: &mut Engine, acc: f32) {
+    fn update(&mut self, engine: &mut Engine, _acc: f32) {
+        self.reload_tuning(engine);
+        // keep shaking and sparking while the scene is frozen under a transition
+        self.camera.tick();
+        engine.profiler.begin("particles");
+        self.particles.update();
+        engine.profiler.end();
+        if let Some(slow_motion) = &mut self.slow_motion {
+            engine.set_time_scale(slow_motion.tick());
+            if slow_motion.is_done() {
+                self.slow_motion = None;
+            }
+        }
+        if let Some(transition) = &mut self.transition {
+            let switch = transition.tick();
+            let done = transition.is_done();
+            if switch {
+                if let Some(next_state) = self.next_state.take() {
+                    if let GameState::GameOver = next_state {
+                        hide_sprite_group(&mut engine.renderer.sprites, &engine.renderer.gpu, 0);
+                    }
+                    self.game_state = next_state;
+                }
+            }
+            if done {
+                // after the crossfade this hides the title screen
+                transition.clear(&mut engine.renderer.sprites, &engine.renderer.gpu);
+                self.transition = None;
+            }
+            // hold the scene still while it is being covered
+            if self.next_state.is_some() {
+                return;
+            }
+        }
         match self.game_state {
             GameState::TitleScreen => {
-                // Check if the space bar is pressed
-                if engine.input.is_key_pressed(engine::Key::Space) {
-                    engine.renderer.sprites.remove_sprite_group(1);
-                    // Transition to the in-game state
+                // Check if the space bar is pressed or the screen tapped
+                if engine.input.is_key_pressed(engine::Key::Space) || engine.pointer.is_tapped() {
+                    // Transition to the in-game state, dissolving the title screen away
                     self.game_state = GameState::InGame;
+                    self.transition = Some(Transition::crossfade(
+                        Overlay {
+                            group: self.start_group,
+                            cover: START_SPRITE_UV,
+                        },
+                        45,
+                    ));
                 }
             }
 
             GameState::InGame => {
+                // quick save and load
+                if engine.input.is_key_pressed(engine::Key::F5) {
+                    if let Err(err) = snapshot::save(self, &snapshot::default_path::<Self>()) {
+                        log::error!(target: "save", "could not save: {err}");
+                    }
+                } else if engine.input.is_key_pressed(engine::Key::F9) {
+                    if let Err(err) = snapshot::load(self, engine, &snapshot::default_path::<Self>()) {
+                        log::error!(target: "save", "could not load: {err}");
+                    }
+                }
+                self.run_commands(engine);
+                self.camera.follow(self.guy.pos);
+                self.difficulty.tick();
+                self.difficulty.set_score(self.score);
+                let spawn_rate = engine.console.get::<f32>("spawn_rate").unwrap_or(1.0);
+                for rule in self.spawner.rules.iter_mut() {
+                    rule.rate = spawn_rate;
+                }
+                self.spawner.rules[self.car_rule].rate *= self.difficulty.sample(&self.car_rate_curve);
+
                 // set the speed of animation for guy. Adjust number after modulo.
                 self.frame_counter = (self.frame_counter + 1) % 5;
                 if self.frame_counter == 0 {
@@ -246,7 +727,7 @@ impl engine::Game for Game {
                     }
                 }
                 // column values
-                let possible_values = [261.33, 378.66, 496.0];
+                let possible_values = &self.tuning.lanes;
                 let mut curr_col = self.guy.pos.x;
                 let position = possible_values
                     .iter()
@@ -255,53 +736,67 @@ impl engine::Game for Game {
                 let curr_index = position.map(|index| index as f32).unwrap_or_default();
 
                 // calculate x position of character
-                // for left or right movement in defined steps (possible_values)
+                // for left or right movement in defined steps (possible_values), by key or swipe
                 let mut dir = 0.0;
-                if engine.input.is_key_pressed(engine::Key::Left) {
+                if engine.input.is_key_pressed(engine::Key::Left) || engine.pointer.swiped(SwipeDir::Left) {
                     dir = -1.0
-                } else if engine.input.is_key_pressed(engine::Key::Right) {
+                } else if engine.input.is_key_pressed(engine::Key::Right) || engine.pointer.swiped(SwipeDir::Right) {
                     dir = 1.0
                 }
 
                 // for jumping
-                if engine.input.is_key_pressed(engine::Key::Up) && !self.guy.is_jumping {
-                    println!("jump!");
+                let jump = engine.input.is_key_pressed(engine::Key::Up) || engine.pointer.swiped(SwipeDir::Up);
+                if jump && !self.guy.is_jumping {
+                    log::debug!(target: "player", "jump");
                     self.guy.is_jumping = true;
+                    // rise for 12 frames, slowing towards the top, then drop to the landing height
+                    let peak = self.guy.pos.y + self.tuning.jump_height;
+                    let fall_ticks = ((peak - JUMP_LANDING_Y) / 2.3).ceil() as u32;
+                    self.guy.y_motion = Some(
+                        Sequence::new(self.guy.pos.y)
+                            .then(peak, 12, Ease::QuadOut)
+                            .then(JUMP_LANDING_Y, fall_ticks, Ease::Linear),
+                    );
                 }
 
-                // track the number of frames the guy has been jumping for
                 if self.guy.is_jumping {
-                    self.guy.fwd_jump_frames += 1;
-
-                    // Continue the animation for 12 frames
-                    if self.guy.fwd_jump_frames <= 12 {
-                        // make the distance traveled between frames progressively less
-                        self.guy.pos.y += self.guy.jump_velocity;
-                        self.guy.jump_velocity -= 0.2;
-                    } else if self.guy.pos.y >= 50.0 {
-                        self.guy.pos.y -= 2.3;
-                    } else {
-                        // End the jumping animation
-                        self.guy.is_jumping = false;
-                        self.guy.fwd_jump_frames = 0;
-                        self.guy.pos.y = 50.0;
-                        self.guy.jump_velocity = 10.0;
+                    if let Some(jump) = &mut self.guy.y_motion {
+                        let mut landed = false;
+                        self.guy.pos.y = jump.tick_then(|_| landed = true);
+                        if landed {
+                            // End the jumping animation
+                            self.guy.is_jumping = false;
+                            self.guy.y_motion = None;
+                        }
                     }
 
-                    if self.cop.is_visible && self.cop.fwd_jump_frames <= 100 {
-                        self.cop.fwd_jump_frames += 1;
+                }
+                self.cop_timers.set_paused(!self.guy.is_jumping);
+                for timer in self.cop_timers.tick() {
+                    match timer {
+                        CopTimer::Grace => self.cop_can_catch = true,
+                        CopTimer::Leave => self.cop_leaving = true,
                     }
                 }
-                if self.cop.fwd_jump_frames > 100 && self.cop.pos.y >= 0.0 {
-                    self.cop.pos.y -= 1.0;
-                } else if self.cop.pos.y < 0.0 && self.cop.is_visible {
-                    // end cop visibility
-                    self.cop.fwd_jump_frames = 0;
-                    self.cop.is_visible = false;
-                    self.cop.pos.y = -50.0;
+                if self.cop_leaving && self.cop.is_visible {
+                    // slide the cop down off the screen, one unit per frame
+                    let cop_y = self.cop.pos.y;
+                    let slide = self.cop.y_motion.get_or_insert_with(|| {
+                        Sequence::new(cop_y).then(-1.0, (cop_y + 1.0).max(1.0) as u32, Ease::Linear)
+                    });
+                    let mut gone = false;
+                    self.cop.pos.y = slide.tick_then(|_| gone = true);
+                    if gone {
+                        // end cop visibility
+                        self.cop_can_catch = false;
+                        self.cop_leaving = false;
+                        self.cop.is_visible = false;
+                        self.cop.pos.y = -50.0;
+                        self.cop.y_motion = None;
+                    }
                 }
 
-                if -1.0 < curr_index as f32 + dir && curr_index as f32 + dir < 3.0 {
+                if -1.0 < curr_index as f32 + dir && curr_index as f32 + dir < possible_values.len() as f32 {
                     let curr_loc = curr_index + dir;
                     curr_col = possible_values[curr_loc as usize];
                 }
@@ -358,183 +853,149 @@ impl engine::Game for Game {
                         }
                     }
                 }
-                let mut rng = rand::thread_rng();
-
-                // spawn new cars
-                if self.car_timer > 0 {
-                    self.car_timer -= 1;
-                } else if self.cars.len() < 32 {
-                    let mut valid_position = false;
-                    let mut new_car_pos = Vec2::default();
-                    while !valid_position {
-                        let uniform = Uniform::new(0, possible_values.len());
-                        let random_index = rng.sample(uniform);
-                        new_car_pos = Vec2 {
-                            x: possible_values[random_index],
-                            y: H + 8.0,
-                        };
-
-                        // Check if the new position overlaps with existing cars
-                        valid_position = !self
-                            .cars
-                            .iter()
-                            .any(|car| new_car_pos.distance(car.pos) <= COLLISION_DISTANCE)
-                            && !self
-                                .coins
-                                .iter()
-                                .any(|coin| new_car_pos.distance(coin.pos) <= COLLISION_DISTANCE);
-                    }
 
-                    self.cars.push(Sprite {
-                        pos: new_car_pos,
-                        vel: Vec2 { x: 0.0, y: -2.0 },
-                    });
-
-                    self.car_timer = rng.gen_range(30..90);
-                }
+                // spawn new cars and coins
+                let radius = self.tuning.collision_distance;
+                self.spawner.update(&mut self.things, &mut self.rng, |world, thing, pos| {
+                    thing.spawn(world, pos, radius)
+                });
                 // update car velocities every frame
-                for car in self.cars.iter_mut() {
-                    car.pos += car.vel;
-                }
+                let car_speed = engine.console.get::<f32>("car_speed").unwrap_or(1.0);
+                self.things.move_all(|t| if *t == Thing::Car { car_speed } else { 0.0 });
                 // if any car is within the catch distance of the guy, mark a collision
                 if !self.guy.is_jumping {
-                    if let Some(_idx) = self
-                        .cars
-                        .iter()
-                        .position(|car| car.pos.distance(self.guy.pos) <= COLLISION_DISTANCE)
-                    {
-                        println!("Score: {}", self.score);
-                        engine.renderer.sprites.remove_sprite_group(0);
-                        self.game_state = GameState::GameOver;
-                    } else if let Some(_idx) = self
-                        .cars
-                        .iter()
-                        .position(|car| car.pos.distance(self.guy.pos) <= COP_DISTANCE)
+                    let is_car = |t: &Thing| *t == Thing::Car;
+                    if !self.things.touching(self.guy.pos, 0.0, is_car).is_empty() {
+                        let god_mode = engine.console.get::<bool>("god_mode").unwrap_or(false);
+                        if self.transition.is_none() && !god_mode {
+                            self.events.emit(GameEvent::Crashed { pos: self.guy.pos });
+                        }
+                    } else if !self
+                        .things
+                        .touching(
+                            self.guy.pos,
+                            self.tuning.cop_distance - self.tuning.collision_distance,
+                            is_car,
+                        )
+                        .is_empty()
                     {
-                        println!("COP!");
+                        log::debug!(target: "cop", "near miss, the cop is coming");
                         if !self.cop.is_visible {
+                            self.camera.add_trauma(0.4);
+                            // a near miss: drop into slow motion and ease back out
+                            let slow_motion = Tween::new(0.35, 1.0, 60).eased(Ease::QuadIn);
+                            engine.set_time_scale(slow_motion.value());
+                            self.slow_motion = Some(slow_motion);
                             self.cop.is_visible = true;
                             self.guy.pos.y = GUY_Y_POS + 100.0;
                             self.cop.pos.y = GUY_Y_POS;
-                            // if the cop is already on the screen and it's been on the screen for more than the collision cooldown of 50 frames
-                        } else if self.cop.is_visible && self.cop.fwd_jump_frames > 50 {
+                            // the cop can't catch the guy for the first 50 frames of jumping,
+                            // and gives up after 100
+                            self.cop_timers.clear();
+                            self.cop_timers.after(50, CopTimer::Grace);
+                            self.cop_timers.after(100, CopTimer::Leave);
+                            // if the cop is already on the screen and the grace period is over
+                        } else if self.cop.is_visible && self.cop_can_catch {
+                            self.record_score();
                             self.game_over = true;
                         }
                     }
                 }
-                // between frames, maintain all the cars on the screen that are above position -8.0
-                self.cars.retain(|car| car.pos.y > -8.0);
+                // between frames, maintain all the cars and coins on the screen that are above position -8.0
+                self.things.cull(|pos| pos.y <= -8.0);
 
                 // if a coin is within the catch distance, add one to the score
-                if let Some(idx) = self
-                    .coins
-                    .iter()
-                    .position(|coin: &Sprite| coin.pos.distance(self.guy.pos) <= COLLISION_DISTANCE)
-                {
-                    self.coins.swap_remove(idx);
-                    self.score += 1
+                let is_coin = |t: &Thing| *t == Thing::Coin;
+                if let Some(&coin) = self.things.touching(self.guy.pos, 0.0, is_coin).first() {
+                    let pos = self.things.pos(coin).unwrap_or(self.guy.pos);
+                    self.events.emit(GameEvent::CoinCollected { pos });
+                    self.things.despawn(coin);
+                    self.things.flush();
                 }
 
-                // Spawn new coins
-                if self.coin_timer > 0 {
-                    self.coin_timer -= 1;
-                } else if self.coins.len() < 32 {
-                    let mut valid_position = false;
-                    let mut new_coin_pos = Vec2::default();
-                    while !valid_position {
-                        let uniform = Uniform::new(0, possible_values.len());
-                        let random_index = rng.sample(uniform);
-                        new_coin_pos = Vec2 {
-                            x: possible_values[random_index],
-                            y: H + 8.0,
-                        };
-
-                        // Check if the new position overlaps with existing cars or coins
-                        valid_position = !self
-                            .cars
-                            .iter()
-                            .any(|car| new_coin_pos.distance(car.pos) <= COLLISION_DISTANCE)
-                            && !self
-                                .coins
-                                .iter()
-                                .any(|coin| new_coin_pos.distance(coin.pos) <= COLLISION_DISTANCE);
-                    }
-                    self.coins.push(Sprite {
-                        pos: new_coin_pos,
-                        vel: Vec2 { x: 0.0, y: -2.0 },
-                    });
-
-                    self.coin_timer = rng.gen_range(30..90);
-                }
                 // Update coins
-                for coin in self.coins.iter_mut() {
-                    coin.pos += coin.vel;
-                }
-                self.coins.retain(|coin| coin.pos.y > -8.0);
+                self.things.move_all(|t| if *t == Thing::Coin { 1.0 } else { 0.0 });
+                self.things.cull(|pos| pos.y <= -8.0);
 
-                generate_scrolling_side(&mut self.pavements, PAVEMENT_SPEED, W, H);
+                generate_scrolling_side(&mut self.pavements, self.tuning.pavement_speed, W, H);
 
-                // Increase speed multipliers over time
-                self.car_speed_multiplier += 0.001 * acc;
-                self.coin_speed_multiplier += 0.001 * acc;
+                // Update cars and coins with the speed for the current difficulty
+                let speed = self.difficulty.sample(&self.speed_curve);
+                self.things.move_all(|_| speed);
 
-                // Update cars with increased speed
-                for car in self.cars.iter_mut() {
-                    car.pos += car.vel * self.car_speed_multiplier;
-                }
-
-                // Update coins with increased speed
-                for coin in self.coins.iter_mut() {
-                    coin.pos += coin.vel * self.coin_speed_multiplier;
-                }
-
-                // coin sound
                 // Check if the guy collides with a coin
-                if let Some(idx) = self
-                    .coins
-                    .iter()
-                    .position(|coin| coin.pos.distance(self.guy.pos) <= COLLISION_DISTANCE)
-                {
-                    // Play the coin sound
-                    self.audio_manager.play(self.coin_sound.clone()).unwrap();
-
+                if let Some(&coin) = self.things.touching(self.guy.pos, 0.0, is_coin).first() {
                     // Remove the collected coin
-                    self.coins.swap_remove(idx);
-
-                    // Increase the score
-                    self.score += 1;
+                    let pos = self.things.pos(coin).unwrap_or(self.guy.pos);
+                    self.events.emit(GameEvent::CoinCollected { pos });
+                    self.things.despawn(coin);
+                    self.things.flush();
                 }
             }
             GameState::GameOver => {
                 // hello
             }
         }
+        // let sound, effects and scoring react to this tick's events
+        engine.profiler.begin("events");
+        EventBus::dispatch(self, |game| &mut game.events);
+        engine.profiler.end();
+    }
+    fn paused(&mut self, engine: &mut Engine, paused: bool) {
+        self.audio.set_paused(paused);
+        if paused {
+            // the menu opens on Resume
+            self.ui.set_focus(0);
+        } else if !matches!(self.game_state, GameState::GameOver) {
+            hide_sprite_group(&mut engine.renderer.sprites, &engine.renderer.gpu, self.ui_group);
+        }
     }
+
     fn render(&mut self, engine: &mut Engine) {
+        // sound follows the time scale; render runs even while time is frozen
+        self.audio.set_rate(engine.time_scale() as f64);
         match self.game_state {
             GameState::TitleScreen => {
                 render_start_sprite(
                     &mut engine.renderer.sprites,
                     &mut engine.renderer.gpu,
-                    self.camera,
+                    self.camera.screen_camera(),
+                    self.start_group,
                     W,
                     H,
                 );
             }
             GameState::InGame => {
-                let score_str = self.score.to_string();
-                let text_len = score_str.len();
+                self.hud.set_text(self.score_text, &self.score.to_string());
+                self.hud
+                    .set_text(self.level_text, &self.difficulty.level().to_string());
+
+                // a car crashes into the guy when its center comes within
+                // the inner circle, and the cop comes at the outer one
+                self.debug.visible = engine.show_debug;
+                self.debug.clear();
+                self.debug.colliders(&self.things);
+                self.debug.circle(self.guy.pos, self.tuning.collision_distance);
+                self.debug.circle(self.guy.pos, self.tuning.cop_distance);
+                self.debug.rect(SPRITE {
+                    center: self.guy.pos,
+                    size: Vec2 { x: 38.4, y: 65.33 },
+                });
+                self.debug.stat("things", self.things.len());
+                self.debug.stat("particles", self.particles.sprite_count());
+                self.debug.engine_stats(engine);
 
                 let sprite_count = self.walls.len()
                     + self.pavements.len()
-                    + self.cars.len()
-                    + self.coins.len()
+                    + self.things.sprite_count()
+                    + self.particles.sprite_count()
+                    + self.debug.sprite_count()
                     + 3;
 
                 engine.renderer.sprites.resize_sprite_group(
                     &engine.renderer.gpu,
                     0,
-                    sprite_count + text_len,
+                    sprite_count + self.hud.sprite_count(),
                 );
 
                 let (transforms, uvs) = engine.renderer.sprites.get_sprites_mut(0);
@@ -604,56 +1065,79 @@ impl engine::Game for Game {
                     *uv = SheetRegion::new(0, 640, 0, 5, 45, 748);
                 }
 
-                // set car
-                let car_start = pavement_start + self.pavements.len();
-
-                for (car, (transform, uv)) in self.cars.iter().zip(
-                    transforms[car_start..]
-                        .iter_mut()
-                        .zip(uvs[car_start..].iter_mut()),
-                ) {
-                    *transform = SPRITE {
-                        center: car.pos,
-                        size: Vec2 { x: 38.4, y: 65.33 },
-                    }
-                    .into();
-                    *uv = SheetRegion::new(0, 27, 525, 3, 27, 32);
-                }
+                // set cars and coins
+                let things_start = pavement_start + self.pavements.len();
+                let particles_start = things_start
+                    + self.things.write_sprites(
+                        &mut transforms[things_start..],
+                        &mut uvs[things_start..],
+                    );
 
-                // set coin
-                let coin_start = car_start + self.cars.len();
-                for (coin, (transform, uv)) in self.coins.iter().zip(
-                    transforms[coin_start..]
-                        .iter_mut()
-                        .zip(uvs[coin_start..].iter_mut()),
-                ) {
-                    *transform = SPRITE {
-                        center: coin.pos,
-                        size: Vec2 { x: 33.0, y: 38.0 },
-                    }
-                    .into();
-                    *uv = SheetRegion::new(0, 20, 480, 2, 16, 16);
-                }
+                // set sparks
+                let debug_start = particles_start
+                    + self.particles.write_sprites(
+                        &mut transforms[particles_start..],
+                        &mut uvs[particles_start..],
+                    );
 
-                let sprite_count = coin_start + self.coins.len();
-                render_game_sprites(&self.font, self.camera, &mut engine.renderer.sprites, sprite_count, score_str, &mut engine.renderer.gpu, H);
+                // set the debug overlay on top of everything
+                let sprite_count = debug_start
+                    + self.debug.write_sprites(
+                        self.camera.camera(),
+                        self.camera.view_size,
+                        &mut transforms[debug_start..],
+                        &mut uvs[debug_start..],
+                    );
+                render_game_sprites(&self.hud, self.camera.camera(), self.camera.view_size, &mut engine.renderer.sprites, sprite_count, &mut engine.renderer.gpu);
             }
             GameState::GameOver => {
-                // the end screen sprite group is now at index 0 after removing the first two groups
                 render_end_sprite(
                     &self.font_end,
                     &mut engine.renderer.sprites,
                     &mut engine.renderer.gpu,
                     self.score,
-                    self.camera,
-                    -30.0,
-                    W,
-                    H,
+                    self.camera.screen_camera(),
+                    EndLayout {
+                        group: self.end_group,
+                        font_y_offset: -30.0,
+                        width: W,
+                        height: H,
+                    },
                 );
+                // the pause menu takes the ui group while paused
+                if !engine.is_paused() {
+                    self.ui.begin(&engine.keys);
+                    if end_summary(&mut self.ui, self.score, self.high_scores.top(5), W, H) {
+                        self.game_over = true;
+                    }
+                    self.ui.end(&mut engine.renderer.sprites, &engine.renderer.gpu, self.ui_group);
+                }
+            }
+        }
+        if let Some(transition) = &self.transition {
+            let area = if transition.kind == TransitionKind::Crossfade {
+                start_sprite_area(W, H)
+            } else {
+                self.active_camera().into()
+            };
+            transition.render(&mut engine.renderer.sprites, &engine.renderer.gpu, area);
+        }
+        if engine.is_paused() {
+            self.ui.begin(&engine.keys);
+            match pause_menu(&mut self.ui, W, H) {
+                Some(PauseChoice::Resume) => engine.set_paused(false),
+                Some(PauseChoice::Quit) => self.game_over = true,
+                None => {}
             }
+            let sprites = &mut engine.renderer.sprites;
+            self.ui.end(sprites, &engine.renderer.gpu, self.ui_group);
+            // the menu stays put however the game camera moves
+            sprites.set_camera(&engine.renderer.gpu, self.ui_group, self.camera.screen_camera());
         }
+        self.dev_text.render(engine, self.active_camera(), self.camera.view_size);
     }
 }
 fn main() {
+    engine::logging::init("info");
     Engine::new(winit::window::WindowBuilder::new()).run::<Game>();
 }